@@ -0,0 +1,103 @@
+use crate::input::Input;
+use anyhow::{anyhow, bail};
+use std::path::PathBuf;
+
+/// Command line arguments shared by every day. Anything that isn't about the input is kept, in
+/// order, for the day itself to look at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cli {
+    pub input: Input,
+    args: Vec<String>,
+}
+
+impl Cli {
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut input = None;
+        let mut rest = Vec::new();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let source = match arg.as_str() {
+                "--test_input" => Input::Test,
+                "--input" => match args.next() {
+                    Some(path) if path == "-" => Input::Stdin,
+                    Some(path) => Input::Path(PathBuf::from(path)),
+                    None => bail!("--input needs a path (or - for stdin)"),
+                },
+                _ => {
+                    rest.push(arg);
+                    continue;
+                }
+            };
+            if input.replace(source).is_some() {
+                bail!("Only one of --input and --test_input may be given");
+            }
+        }
+
+        Ok(Self {
+            input: input.unwrap_or(Input::Private),
+            args: rest,
+        })
+    }
+
+    pub fn read_input(&self, day: u8) -> anyhow::Result<String> {
+        self.input.read(day)
+    }
+
+    // The arguments left over after the input options were taken out
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.args.iter().any(|a| a == name)
+    }
+
+    // Value following an option, e.g. value("--from") for `--from svr`
+    pub fn value(&self, name: &str) -> anyhow::Result<Option<&str>> {
+        match self.args.iter().position(|a| a == name) {
+            Some(i) => self
+                .args
+                .get(i + 1)
+                .map(|v| Some(v.as_str()))
+                .ok_or_else(|| anyhow!("{name} needs a value")),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> anyhow::Result<Cli> {
+        Cli::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_input_options() {
+        assert_eq!(parse(&[]).unwrap().input, Input::Private);
+        assert_eq!(parse(&["--test_input"]).unwrap().input, Input::Test);
+        assert_eq!(parse(&["--input", "-"]).unwrap().input, Input::Stdin);
+        assert_eq!(
+            parse(&["--input", "a.txt"]).unwrap().input,
+            Input::Path(PathBuf::from("a.txt"))
+        );
+        assert!(parse(&["--input"]).is_err());
+        assert!(parse(&["--input", "a.txt", "--test_input"]).is_err());
+    }
+
+    #[test]
+    fn test_remaining_args() {
+        let cli = parse(&["7", "--test_input", "--part", "2"]).unwrap();
+        assert_eq!(cli.args(), ["7", "--part", "2"]);
+        assert!(cli.flag("--part"));
+        assert_eq!(cli.value("--part").unwrap(), Some("2"));
+        assert_eq!(cli.value("--explain").unwrap(), None);
+        assert!(parse(&["--from"]).unwrap().value("--from").is_err());
+    }
+}
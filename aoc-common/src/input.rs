@@ -0,0 +1,86 @@
+use anyhow::Context;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Where a day reads its puzzle input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// `input/dayNN.txt` under the workspace root
+    Private,
+    /// `test_input/dayNN.txt` under the workspace root
+    Test,
+    /// An explicit file given with `--input <path>`
+    Path(PathBuf),
+    /// `--input -`
+    Stdin,
+}
+
+impl Input {
+    // The name answers for this input are recorded under, None for stdin
+    pub fn name(&self) -> Option<String> {
+        match self {
+            Input::Private => Some("input".to_string()),
+            Input::Test => Some("test_input".to_string()),
+            Input::Path(path) => path.file_stem().map(|s| s.to_string_lossy().into_owned()),
+            Input::Stdin => None,
+        }
+    }
+
+    pub fn read(&self, day: u8) -> anyhow::Result<String> {
+        match self {
+            Input::Private => read_file(workspace_root().join("input").join(file_name(day))),
+            Input::Test => read_file(workspace_root().join("test_input").join(file_name(day))),
+            Input::Path(path) => read_file(path),
+            Input::Stdin => {
+                let mut buffer = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut buffer)
+                    .context("Failed to read input from stdin")?;
+                Ok(buffer)
+            }
+        }
+    }
+}
+
+// The directory holding the workspace Cargo.toml, so inputs resolve no matter where we're run from
+pub fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("aoc-common lives inside the workspace")
+}
+
+/// Reads a file from `test_input/` by name, e.g. `read_test_input("day11part2.txt")`.
+pub fn read_test_input(name: &str) -> anyhow::Result<String> {
+    read_file(workspace_root().join("test_input").join(name))
+}
+
+fn file_name(day: u8) -> String {
+    format!("day{day:02}.txt")
+}
+
+fn read_file(path: impl AsRef<Path>) -> anyhow::Result<String> {
+    let path = path.as_ref();
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_test_input() {
+        assert!(Input::Test.read(1).unwrap().starts_with("L68"));
+        assert_eq!(
+            read_test_input("day01.txt").unwrap(),
+            Input::Test.read(1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_missing_file_is_named() {
+        let err = Input::Path(PathBuf::from("no/such/day99.txt"))
+            .read(99)
+            .unwrap_err();
+        assert!(err.to_string().contains("no/such/day99.txt"));
+    }
+}
@@ -0,0 +1,177 @@
+mod cli;
+mod input;
+mod output;
+mod timing;
+mod verify;
+
+pub use cli::Cli;
+pub use input::{Input, read_test_input, workspace_root};
+pub use output::{Format, to_json};
+pub use timing::{StageStats, Timer, Timings, bench};
+pub use verify::{AnswerStore, Mismatch, Verification};
+
+use anyhow::{Context, bail};
+use std::fmt::{Display, Formatter};
+
+/// Every day exposes one of these as `dayNN::solve`
+pub type Solver = fn(&str) -> anyhow::Result<Answers>;
+
+/// The answers a day's solver produced. A part is `None` when the input doesn't have what that
+/// part needs (e.g. the day11 samples are split across two files) or the day has no such part.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    pub timings: Timings,
+    /// More about how the answers were found, e.g. day03's `--explain`, printed after them
+    pub report: Option<String>,
+}
+
+impl Answers {
+    pub fn new(part1: impl Display, part2: impl Display) -> Self {
+        Self {
+            part1: Some(part1.to_string()),
+            part2: Some(part2.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn part1_only(part1: impl Display) -> Self {
+        Self {
+            part1: Some(part1.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_timings(mut self, timer: Timer) -> Self {
+        self.timings = timer.finish();
+        self
+    }
+
+    pub fn with_report(mut self, report: String) -> Self {
+        self.report = Some(report);
+        self
+    }
+
+    // Returns None for parts that weren't computed or don't exist
+    pub fn part(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for part in 1..=2 {
+            if let Some(answer) = self.part(part) {
+                writeln!(f, "Part {part}: {answer}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Options about how a day is run, understood by every day and the runner:
+/// `--time`, `--bench N`, `--format text|json` and `--verify`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    pub time: bool,
+    pub bench: Option<usize>,
+    pub format: Format,
+    pub verify: bool,
+}
+
+impl RunOptions {
+    pub fn from_cli(cli: &Cli) -> anyhow::Result<Self> {
+        let bench = match cli.value("--bench")? {
+            Some(runs) => Some(
+                runs.parse::<usize>()
+                    .with_context(|| format!("Invalid number of runs {runs}"))?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            time: cli.flag("--time"),
+            bench,
+            format: cli.value("--format")?.unwrap_or("text").parse()?,
+            verify: cli.flag("--verify"),
+        })
+    }
+
+    // Solves once, or `--bench N` times keeping the stats
+    pub fn solve(
+        &self,
+        solve: impl Fn(&str) -> anyhow::Result<Answers>,
+        input: &str,
+    ) -> anyhow::Result<(Answers, Option<Vec<StageStats>>)> {
+        match self.bench {
+            Some(runs) => bench(solve, input, runs).map(|(answers, stats)| (answers, Some(stats))),
+            None => solve(input).map(|answers| (answers, None)),
+        }
+    }
+
+    // The answers in the chosen format, followed by the `time` and `bench` lines asked for in
+    // text mode. JSON always carries the timings.
+    pub fn render(&self, day: u8, answers: &Answers, stats: Option<&[StageStats]>) -> String {
+        if self.format == Format::Json {
+            return to_json(day, answers, stats) + "\n";
+        }
+
+        let mut report = answers.to_string();
+        if self.time {
+            report += &answers.timings.report(day);
+        }
+        for stage in stats.unwrap_or_default() {
+            report += &stage.report(day);
+        }
+
+        report
+    }
+
+    // Verification and other reports go to stderr in JSON mode so stdout stays parseable
+    pub fn print_verification(&self, verification: &Verification) {
+        self.print_report(verification);
+    }
+
+    pub fn print_report(&self, report: impl Display) {
+        match self.format {
+            Format::Text => print!("{report}"),
+            Format::Json => eprint!("{report}"),
+        }
+    }
+}
+
+/// Runs a day from the command line: reads its input, solves it and prints the answers, plus
+/// timings with `--time` or `--bench N`, as text or with `--format json`. With `--verify` the
+/// answers are also checked against `answers.toml`, failing on any mismatch or if nothing is
+/// recorded for the input. Any report the solver attached comes after the answers, ahead of the
+/// check so a mismatch doesn't hide it. `solve` is the day's `Solver` or a closure over options only that day understands.
+pub fn run(day: u8, solve: impl Fn(&str) -> anyhow::Result<Answers>) -> anyhow::Result<()> {
+    let cli = Cli::from_env()?;
+    let options = RunOptions::from_cli(&cli)?;
+    let input = cli.read_input(day)?;
+    let (answers, stats) = options.solve(solve, &input)?;
+
+    print!("{}", options.render(day, &answers, stats.as_deref()));
+    if let Some(report) = &answers.report {
+        options.print_report(report);
+    }
+
+    if options.verify {
+        let verification = AnswerStore::load()?.check(day, &cli.input, &answers)?;
+        options.print_verification(&verification);
+        if verification.mismatches.is_none() {
+            bail!("Day {day:02} has no recorded answers to verify against");
+        }
+        if !verification.passed() {
+            bail!("Day {day:02} doesn't match the recorded answers");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use crate::{Answers, StageStats};
+use anyhow::bail;
+use std::str::FromStr;
+
+/// How a run's results are printed, picked with `--format text|json`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => bail!("Unknown format {s}, expected text or json"),
+        }
+    }
+}
+
+/// A single-line JSON object describing one day's run:
+///
+/// ```json
+/// {"day":9,"part1":50,"part2":24,"timings":{"parse":1200,"part1":300,"part2":900,"total":2400}}
+/// ```
+///
+/// Timings are in nanoseconds. With `stats` from `--bench` a `"bench"` object is added holding
+/// the run count and the min, median and max of every stage.
+pub fn to_json(day: u8, answers: &Answers, stats: Option<&[StageStats]>) -> String {
+    let timings = answers
+        .timings
+        .with_total()
+        .map(|(stage, d)| format!("{}:{}", json_string(stage), d.as_nanos()))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let mut json = format!(
+        "{{\"day\":{day},\"part1\":{},\"part2\":{},\"timings\":{{{timings}}}",
+        json_answer(answers.part1.as_deref()),
+        json_answer(answers.part2.as_deref()),
+    );
+
+    if let Some(stats) = stats {
+        let runs = stats.first().map_or(0, |s| s.runs);
+        let stages = stats
+            .iter()
+            .map(|s| {
+                format!(
+                    "{}:{{\"min\":{},\"median\":{},\"max\":{}}}",
+                    json_string(s.stage),
+                    s.min.as_nanos(),
+                    s.median.as_nanos(),
+                    s.max.as_nanos()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        json += &format!(",\"bench\":{{\"runs\":{runs},\"stages\":{{{stages}}}}}");
+    }
+
+    json + "}"
+}
+
+// Integers that fit an i64 or u64 go out as numbers, anything else (bigger numbers included) as a
+// string so no consumer silently loses precision
+fn json_answer(answer: Option<&str>) -> String {
+    match answer {
+        None => "null".to_string(),
+        Some(a) if a.parse::<i64>().is_ok() || a.parse::<u64>().is_ok() => a.to_string(),
+        Some(a) => json_string(a),
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json += "\\\"",
+            '\\' => json += "\\\\",
+            '\n' => json += "\\n",
+            c if c.is_control() => json += &format!("\\u{:04x}", c as u32),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Timings;
+    use std::time::Duration;
+
+    #[test]
+    fn test_json_answer() {
+        assert_eq!(json_answer(None), "null");
+        assert_eq!(json_answer(Some("-42")), "-42");
+        assert_eq!(
+            json_answer(Some("18446744073709551615")),
+            "18446744073709551615"
+        );
+        assert_eq!(
+            json_answer(Some("18446744073709551616")),
+            "\"18446744073709551616\""
+        );
+        assert_eq!(json_answer(Some("a \"b\"")), r#""a \"b\"""#);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let answers = Answers {
+            timings: Timings {
+                stages: vec![("parse", Duration::from_nanos(5))],
+            },
+            ..Answers::part1_only(3)
+        };
+        assert_eq!(
+            to_json(12, &answers, None),
+            r#"{"day":12,"part1":3,"part2":null,"timings":{"parse":5,"total":5}}"#
+        );
+
+        let stats = [StageStats {
+            stage: "parse",
+            runs: 3,
+            min: Duration::from_nanos(1),
+            median: Duration::from_nanos(2),
+            max: Duration::from_nanos(3),
+        }];
+        assert!(
+            to_json(12, &answers, Some(&stats)).ends_with(
+                r#","bench":{"runs":3,"stages":{"parse":{"min":1,"median":2,"max":3}}}}"#
+            )
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use crate::Answers;
+use anyhow::bail;
+use std::time::{Duration, Instant};
+
+/// Records how long each stage of a solve took. Stages are named by the solver, usually `parse`,
+/// `part1` and `part2`, or `parts` when both parts come out of the same work.
+#[derive(Debug)]
+pub struct Timer {
+    last: Instant,
+    timings: Timings,
+}
+
+impl Timer {
+    pub fn start() -> Self {
+        Self {
+            last: Instant::now(),
+            timings: Timings::default(),
+        }
+    }
+
+    // Records the time since the previous lap (or the start) against `stage`
+    pub fn lap(&mut self, stage: &'static str) {
+        let now = Instant::now();
+        self.timings.stages.push((stage, now - self.last));
+        self.last = now;
+    }
+
+    pub fn finish(self) -> Timings {
+        self.timings
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub stages: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.stages.iter().map(|(_, d)| *d).sum()
+    }
+
+    // Every stage followed by the total
+    pub fn with_total(&self) -> impl Iterator<Item = (&'static str, Duration)> + '_ {
+        self.stages
+            .iter()
+            .copied()
+            .chain(std::iter::once(("total", self.total())))
+    }
+
+    // One `time` line per stage, e.g. `time day=07 stage=parse ns=41250`
+    pub fn report(&self, day: u8) -> String {
+        self.with_total()
+            .map(|(stage, d)| format!("time day={day:02} stage={stage} ns={}\n", d.as_nanos()))
+            .collect()
+    }
+}
+
+/// Spread of one stage's duration over a number of runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageStats {
+    pub stage: &'static str,
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl StageStats {
+    // One `bench` line, e.g. `bench day=07 stage=parse runs=10 min_ns=1 median_ns=2 max_ns=3`
+    pub fn report(&self, day: u8) -> String {
+        format!(
+            "bench day={day:02} stage={} runs={} min_ns={} median_ns={} max_ns={}\n",
+            self.stage,
+            self.runs,
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.max.as_nanos()
+        )
+    }
+}
+
+/// Solves `input` `runs` times. Returns the answers of the first run together with the spread of
+/// every stage (and the total) across all of them.
+pub fn bench(
+    solve: impl Fn(&str) -> anyhow::Result<Answers>,
+    input: &str,
+    runs: usize,
+) -> anyhow::Result<(Answers, Vec<StageStats>)> {
+    if runs == 0 {
+        bail!("Need at least one run to benchmark");
+    }
+
+    let first = solve(input)?;
+    let mut samples = vec![first.timings.clone()];
+    for _ in 1..runs {
+        samples.push(solve(input)?.timings);
+    }
+
+    Ok((first, stage_stats(&samples)))
+}
+
+fn stage_stats(samples: &[Timings]) -> Vec<StageStats> {
+    let Some(first) = samples.first() else {
+        return Vec::new();
+    };
+
+    first
+        .with_total()
+        .enumerate()
+        .map(|(i, (stage, _))| {
+            let mut durations = samples
+                .iter()
+                .filter_map(|t| t.with_total().nth(i))
+                .map(|(_, d)| d)
+                .collect::<Vec<_>>();
+            durations.sort_unstable();
+
+            StageStats {
+                stage,
+                runs: durations.len(),
+                min: durations[0],
+                median: durations[durations.len() / 2],
+                max: durations[durations.len() - 1],
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timings(parse: u64, part1: u64) -> Timings {
+        Timings {
+            stages: vec![
+                ("parse", Duration::from_nanos(parse)),
+                ("part1", Duration::from_nanos(part1)),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        assert_eq!(
+            timings(5, 7).report(7),
+            "time day=07 stage=parse ns=5\n\
+             time day=07 stage=part1 ns=7\n\
+             time day=07 stage=total ns=12\n"
+        );
+    }
+
+    #[test]
+    fn test_stage_stats() {
+        let stats = stage_stats(&[timings(5, 1), timings(3, 2), timings(4, 9)]);
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[0].stage, "parse");
+        assert_eq!(stats[0].min, Duration::from_nanos(3));
+        assert_eq!(stats[0].median, Duration::from_nanos(4));
+        assert_eq!(stats[0].max, Duration::from_nanos(5));
+        assert_eq!(stats[2].stage, "total");
+        assert_eq!(stats[2].median, Duration::from_nanos(6));
+        assert_eq!(
+            stats[1].report(1),
+            "bench day=01 stage=part1 runs=3 min_ns=1 median_ns=2 max_ns=9\n"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use crate::Answers;
+use crate::input::{Input, workspace_root};
+use anyhow::{Context, bail};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use toml::{Table, Value};
+
+/// The known answers from `answers.toml`, keyed by day, input name and part:
+///
+/// ```toml
+/// [day07.test_input]
+/// part1 = 21
+/// part2 = 40
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnswerStore {
+    days: Table,
+}
+
+impl AnswerStore {
+    pub fn load() -> anyhow::Result<Self> {
+        let path = workspace_root().join("answers.toml");
+        let s = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        s.parse()
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    // Returns None if nothing is recorded for this day and input
+    pub fn expected(&self, day: u8, input_name: &str) -> anyhow::Result<Option<Answers>> {
+        let Some(entry) = self
+            .days
+            .get(&format!("day{day:02}"))
+            .and_then(|d| d.get(input_name))
+        else {
+            return Ok(None);
+        };
+
+        let part = |name: &str| match entry.get(name) {
+            None => Ok(None),
+            Some(Value::Integer(n)) => Ok(Some(n.to_string())),
+            Some(Value::String(s)) => Ok(Some(s.clone())),
+            Some(other) => bail!("day{day:02}.{input_name}.{name} should be a number, got {other}"),
+        };
+
+        Ok(Some(Answers {
+            part1: part("part1")?,
+            part2: part("part2")?,
+            ..Answers::default()
+        }))
+    }
+
+    /// Compares computed answers against the recorded ones. Only parts that have a recorded answer
+    /// are checked; returns None if nothing at all is recorded for this day and input.
+    pub fn verify(
+        &self,
+        day: u8,
+        input_name: &str,
+        answers: &Answers,
+    ) -> anyhow::Result<Option<Vec<Mismatch>>> {
+        let Some(expected) = self.expected(day, input_name)? else {
+            return Ok(None);
+        };
+
+        let mismatches = (1..=2)
+            .filter_map(|part| {
+                let expected = expected.part(part)?;
+                let actual = answers.part(part);
+                (actual != Some(expected)).then(|| Mismatch {
+                    part,
+                    expected: expected.to_string(),
+                    actual: actual.map(String::from),
+                })
+            })
+            .collect();
+
+        Ok(Some(mismatches))
+    }
+
+    // Like verify, but looks the input name up from where the input came from
+    pub fn check(&self, day: u8, input: &Input, answers: &Answers) -> anyhow::Result<Verification> {
+        let Some(input_name) = input.name() else {
+            bail!("Answers read from stdin can't be verified");
+        };
+        let mismatches = self.verify(day, &input_name, answers)?;
+
+        Ok(Verification {
+            day,
+            input_name,
+            mismatches,
+        })
+    }
+}
+
+/// The outcome of checking one day's answers, printable as a short report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    pub day: u8,
+    pub input_name: String,
+    // None if nothing is recorded for this day and input
+    pub mismatches: Option<Vec<Mismatch>>,
+}
+
+impl Verification {
+    /// True only if there were answers to check and they all matched. Nothing recorded means
+    /// nothing was checked, which a regression run shouldn't count as a pass.
+    pub fn passed(&self) -> bool {
+        self.mismatches.as_ref().is_some_and(|m| m.is_empty())
+    }
+}
+
+impl Display for Verification {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Day {:02} ({}): ", self.day, self.input_name)?;
+        match &self.mismatches {
+            None => writeln!(f, "no recorded answers"),
+            Some(mismatches) if mismatches.is_empty() => writeln!(f, "ok"),
+            Some(mismatches) => {
+                writeln!(f, "{} mismatch(es)", mismatches.len())?;
+                for mismatch in mismatches {
+                    writeln!(f, "  {mismatch}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl FromStr for AnswerStore {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            days: s.parse::<Table>()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub part: u8,
+    pub expected: String,
+    pub actual: Option<String>,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Part {}: expected {}, got {}",
+            self.part,
+            self.expected,
+            self.actual.as_deref().unwrap_or("nothing")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STORE: &str = r#"
+        [day07.test_input]
+        part1 = 21
+        part2 = "40"
+
+        [day11.day11part2]
+        part2 = 2
+    "#;
+
+    #[test]
+    fn test_expected() {
+        let store = STORE.parse::<AnswerStore>().unwrap();
+        assert_eq!(
+            store.expected(7, "test_input").unwrap(),
+            Some(Answers::new(21, 40))
+        );
+        assert_eq!(store.expected(7, "input").unwrap(), None);
+        assert_eq!(
+            store.expected(11, "day11part2").unwrap(),
+            Some(Answers {
+                part1: None,
+                part2: Some("2".to_string()),
+                ..Answers::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let store = STORE.parse::<AnswerStore>().unwrap();
+        let check = |answers| store.verify(7, "test_input", &answers).unwrap().unwrap();
+
+        assert!(check(Answers::new(21, 40)).is_empty());
+        assert_eq!(
+            check(Answers::part1_only(22)),
+            [
+                Mismatch {
+                    part: 1,
+                    expected: "21".to_string(),
+                    actual: Some("22".to_string()),
+                },
+                Mismatch {
+                    part: 2,
+                    expected: "40".to_string(),
+                    actual: None,
+                },
+            ]
+        );
+        assert!(
+            store
+                .verify(1, "test_input", &Answers::new(1, 2))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_passed() {
+        let store = STORE.parse::<AnswerStore>().unwrap();
+        let check = |day, input: &Input| store.check(day, input, &Answers::new(21, 40)).unwrap();
+
+        assert!(check(7, &Input::Test).passed());
+        assert!(
+            !store
+                .check(7, &Input::Test, &Answers::new(21, 41))
+                .unwrap()
+                .passed()
+        );
+
+        let unrecorded = check(7, &Input::Private);
+        assert!(!unrecorded.passed());
+        assert_eq!(
+            unrecorded.to_string(),
+            "Day 07 (input): no recorded answers\n"
+        );
+        assert!(!check(1, &Input::Test).passed());
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{Context, anyhow, bail};
+use aoc_common::{AnswerStore, Cli, Format, Input, RunOptions, Solver};
+use std::ops::RangeInclusive;
+
+// Index 0 is day 1
+const SOLVERS: [Solver; 12] = [
+    day01::solve,
+    day02::solve,
+    day03::solve,
+    day04::solve,
+    day05::solve,
+    day06::solve,
+    day07::solve,
+    day08::solve,
+    day09::solve,
+    day10::solve,
+    day11::solve,
+    day12::solve,
+];
+
+// Usage: aoc25 [all | DAY | FIRST-LAST] [--part 1|2] [--time] [--bench N] [--format text|json]
+//              [--verify] [--test_input | --input <path>]
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::from_env()?;
+    let options = Options::from_args(cli.args())?;
+    let run_options = RunOptions::from_cli(&cli)?;
+
+    // An explicit file or stdin can only be one day's input
+    let explicit = matches!(cli.input, Input::Path(_) | Input::Stdin);
+    if explicit && options.days.start() != options.days.end() {
+        bail!("--input can only be used when running a single day");
+    }
+
+    let store = if run_options.verify {
+        Some(AnswerStore::load()?)
+    } else {
+        None
+    };
+    let mut failed = Vec::new();
+
+    for day in options.days {
+        let input = cli.read_input(day as u8)?;
+        let (mut answers, stats) = run_options.solve(solver(day, &cli.input), &input)?;
+        let verification = match &store {
+            Some(store) => Some(store.check(day as u8, &cli.input, &answers)?),
+            None => None,
+        };
+
+        // Only show the part asked for
+        match options.part {
+            Some(1) => answers.part2 = None,
+            Some(2) => answers.part1 = None,
+            _ => {}
+        }
+
+        if run_options.format == Format::Text {
+            println!("Day {day:02}");
+        }
+        print!(
+            "{}",
+            run_options.render(day as u8, &answers, stats.as_deref())
+        );
+
+        if let Some(verification) = verification {
+            run_options.print_verification(&verification);
+            if !verification.passed() {
+                failed.push(day);
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        bail!("Days {failed:?} don't match the recorded answers or have none");
+    }
+
+    Ok(())
+}
+
+// The day's solver for an input. The day08 example joins fewer pairs than the puzzle input.
+fn solver(day: usize, input: &Input) -> Solver {
+    match (day, input) {
+        (8, Input::Test) => |input| day08::solve_with_connections(input, day08::SAMPLE_CONNECTIONS),
+        _ => SOLVERS[day - 1],
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    days: RangeInclusive<usize>,
+    part: Option<u8>,
+}
+
+impl Options {
+    fn from_args(args: &[String]) -> anyhow::Result<Self> {
+        let mut days = None;
+        let mut part = None;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--part" => {
+                    let value = args.next().ok_or_else(|| anyhow!("--part needs a value"))?;
+                    part = match value.as_str() {
+                        "1" => Some(1),
+                        "2" => Some(2),
+                        _ => bail!("Invalid part {value}, expected 1 or 2"),
+                    };
+                }
+                // Handled by RunOptions
+                "--time" | "--verify" => {}
+                "--bench" | "--format" => {
+                    args.next();
+                }
+                _ if arg.starts_with("--") => bail!("Unrecognized option {arg}"),
+                _ => {
+                    if days.is_some() {
+                        bail!("Days given more than once: {arg}");
+                    }
+                    days = Some(Self::days_from_str(arg)?);
+                }
+            }
+        }
+
+        Ok(Self {
+            days: days.unwrap_or(1..=SOLVERS.len()),
+            part,
+        })
+    }
+
+    // Accepts "all", a single day ("7") or an inclusive range ("3-9")
+    fn days_from_str(s: &str) -> anyhow::Result<RangeInclusive<usize>> {
+        if s == "all" {
+            return Ok(1..=SOLVERS.len());
+        }
+
+        let (first, last) = s.split_once('-').unwrap_or((s, s));
+        let first = first
+            .parse::<usize>()
+            .with_context(|| format!("Invalid day {s}"))?;
+        let last = last
+            .parse::<usize>()
+            .with_context(|| format!("Invalid day {s}"))?;
+
+        if first == 0 || last > SOLVERS.len() || first > last {
+            bail!("Days must be between 1 and {}, got {s}", SOLVERS.len());
+        }
+
+        Ok(first..=last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_from_str() {
+        assert_eq!(Options::days_from_str("7").unwrap(), 7..=7);
+        assert_eq!(Options::days_from_str("3-9").unwrap(), 3..=9);
+        assert_eq!(Options::days_from_str("all").unwrap(), 1..=12);
+        assert!(Options::days_from_str("0").is_err());
+        assert!(Options::days_from_str("13").is_err());
+        assert!(Options::days_from_str("9-3").is_err());
+        assert!(Options::days_from_str("x").is_err());
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args = ["7", "--part", "2", "--bench", "5", "--verify"].map(String::from);
+        assert_eq!(
+            Options::from_args(&args).unwrap(),
+            Options {
+                days: 7..=7,
+                part: Some(2),
+            }
+        );
+        assert!(Options::from_args(&["--part".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_recorded_answers() {
+        let store = AnswerStore::load().unwrap();
+        for day in 1..=SOLVERS.len() {
+            let input = Input::Test.read(day as u8).unwrap();
+            let answers = solver(day, &Input::Test)(&input).unwrap();
+            let mismatches = store.verify(day as u8, "test_input", &answers).unwrap();
+            assert_eq!(mismatches.unwrap_or_default(), [], "day {day}");
+        }
+
+        let answers =
+            day11::solve(&aoc_common::read_test_input("day11part2.txt").unwrap()).unwrap();
+        let mismatches = store.verify(11, "day11part2", &answers).unwrap();
+        assert_eq!(mismatches, Some(Vec::new()));
+    }
+}
@@ -0,0 +1,64 @@
+use anyhow::bail;
+use aoc_common::{Answers, Timer};
+
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let mut timer = Timer::start();
+    let mut rotations = Vec::new();
+    for line in input.lines() {
+        if line.is_empty() {
+            continue;
+        }
+        let direction = match line.chars().next() {
+            Some(c @ ('L' | 'R')) => c,
+            _ => bail!("Invalid direction in {line}"),
+        };
+        rotations.push((direction, get_magnitude(line)?));
+    }
+    timer.lap("parse");
+
+    // Part 1 counts the rotations that end on 0
+    let mut number = 50;
+    let mut part1_result = 0;
+    for &(direction, magnitude) in &rotations {
+        number = match direction {
+            'L' => (number - magnitude).rem_euclid(100),
+            _ => (number + magnitude).rem_euclid(100),
+        };
+        if number == 0 {
+            part1_result += 1;
+        }
+    }
+    timer.lap("part1");
+
+    // Part 2 counts every time the dial passes 0 along the way too
+    let mut number = 50;
+    let mut part2_result = 0;
+    for (direction, magnitude) in rotations {
+        match direction {
+            'L' => {
+                part2_result += if magnitude < number {
+                    0
+                } else if magnitude == number {
+                    1
+                } else {
+                    (magnitude - number) / 100
+                };
+                if magnitude > number && number > 0 {
+                    part2_result += 1;
+                }
+                number = (number - magnitude).rem_euclid(100);
+            }
+            _ => {
+                part2_result += (number + magnitude) / 100;
+                number = (number + magnitude).rem_euclid(100);
+            }
+        }
+    }
+    timer.lap("part2");
+
+    Ok(Answers::new(part1_result, part2_result).with_timings(timer))
+}
+
+fn get_magnitude(line: &str) -> anyhow::Result<i32> {
+    Ok(line.chars().skip(1).collect::<String>().parse::<i32>()?)
+}
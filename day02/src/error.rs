@@ -0,0 +1,41 @@
+use std::fmt::{Display, Formatter};
+
+/// Why a range was rejected, with the token and its byte offset in the input, counted from 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub token: String,
+    pub offset: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The token isn't two numbers joined by a `-`
+    Malformed,
+    /// One end of the range isn't a number that fits in a u64
+    BadNumber(String),
+    /// The range ends before it starts
+    Reversed { lo: u64, hi: u64 },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "range {:?} at byte {}: {}",
+            self.token, self.offset, self.kind
+        )
+    }
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "expected a range like 11-22"),
+            Self::BadNumber(number) => write!(f, "{number:?} is not a number"),
+            Self::Reversed { lo, hi } => write!(f, "{lo} is greater than {hi}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
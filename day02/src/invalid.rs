@@ -0,0 +1,186 @@
+// Lists the invalid IDs in a range one at a time, a digit length at a time. The IDs repeating a
+// block of some length run through the blocks in order, so merging those runs, one per block
+// length, keeps them in order while only holding the next ID from each. Palindromes run through
+// their first halves in the same way.
+
+use crate::rules::{Pattern, Rule};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::RangeInclusive;
+
+/// Why an ID is invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// A block `period` digits long repeated `repeats` times
+    Repeated {
+        block: u64,
+        period: u32,
+        repeats: u32,
+    },
+    Palindrome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidId {
+    pub id: u64,
+    pub reason: Reason,
+}
+
+/// The invalid IDs in a range in increasing order
+pub struct InvalidIds {
+    rule: Rule,
+    lengths: Vec<(u32, u128, u128)>,
+    // The digit length being listed
+    len: u32,
+    // The next ID from each block length
+    runs: BinaryHeap<Reverse<Run>>,
+    // The palindromes' halves still to go
+    halves: RangeInclusive<u128>,
+}
+
+// The IDs repeating blocks of one length, ordered by the next ID
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Run {
+    id: u128,
+    period: u32,
+    block: u128,
+    last: u128,
+    // How far apart the IDs are
+    step: u128,
+}
+
+impl InvalidIds {
+    pub(crate) fn new(rule: Rule, lo: u64, hi: u64) -> Self {
+        let mut lengths = rule.lengths(lo, hi).collect::<Vec<_>>();
+        lengths.reverse();
+
+        Self {
+            rule,
+            lengths,
+            len: 0,
+            runs: BinaryHeap::new(),
+            halves: RangeInclusive::new(1, 0),
+        }
+    }
+
+    // Sets up the runs for the next digit length, false once there are none left
+    fn next_length(&mut self) -> bool {
+        let Some((len, lo, hi)) = self.lengths.pop() else {
+            return false;
+        };
+
+        self.len = len;
+        if self.rule.pattern() == Pattern::Palindrome {
+            if let Some((first, last)) = self.rule.halves(lo, hi, len) {
+                self.halves = first..=last;
+            }
+        } else {
+            for period in self.rule.periods(len) {
+                if let Some((step, first, last)) = self.rule.blocks(lo, hi, len, period) {
+                    self.runs.push(Reverse(Run {
+                        id: first * step,
+                        period,
+                        block: first,
+                        last,
+                        step,
+                    }));
+                }
+            }
+        }
+
+        true
+    }
+}
+
+impl Iterator for InvalidIds {
+    type Item = InvalidId;
+
+    fn next(&mut self) -> Option<InvalidId> {
+        loop {
+            if let Some(Reverse(run)) = self.runs.pop() {
+                let Run {
+                    id, period, block, ..
+                } = run;
+                if block < run.last {
+                    self.runs.push(Reverse(Run {
+                        id: id + run.step,
+                        block: block + 1,
+                        ..run
+                    }));
+                }
+                // With overlapping block lengths, each ID comes from its shortest block
+                if matches!(self.rule.pattern(), Pattern::AtLeastRepeats(_))
+                    && self.rule.shortest_period(id as u64) != period as usize
+                {
+                    continue;
+                }
+
+                return Some(InvalidId {
+                    id: id as u64,
+                    reason: Reason::Repeated {
+                        block: block as u64,
+                        period,
+                        repeats: self.len / period,
+                    },
+                });
+            }
+
+            if let Some(half) = self.halves.next() {
+                return Some(InvalidId {
+                    id: self.rule.palindrome(half, self.len) as u64,
+                    reason: Reason::Palindrome,
+                });
+            }
+
+            if !self.next_length() {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rules::{Pattern, Rule};
+
+    #[test]
+    fn test_against_matches() {
+        let patterns = [
+            Pattern::Repeats(2),
+            Pattern::AtLeastRepeats(2),
+            Pattern::AtLeastRepeats(3),
+            Pattern::BlockLength(2),
+            Pattern::Palindrome,
+        ];
+        for base in [2, 10, 16] {
+            for pattern in patterns {
+                let rule = Rule::new(pattern).unwrap().in_base(base).unwrap();
+                for (lo, hi) in [(0, 5000), (4095, 4097), (65_000, 70_000)] {
+                    let listed = rule.invalid_ids(lo, hi).map(|i| i.id).collect::<Vec<_>>();
+                    let expected = (lo..=hi).filter(|&id| rule.matches(id)).collect::<Vec<_>>();
+                    assert_eq!(listed, expected, "{rule:?} {lo}-{hi}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reasons() {
+        let reasons = |rule: Rule| {
+            rule.invalid_ids(1100, 1111)
+                .map(|i| (i.id, i.reason))
+                .collect::<Vec<_>>()
+        };
+        let repeated = |block, period, repeats| super::Reason::Repeated {
+            block,
+            period,
+            repeats,
+        };
+        assert_eq!(reasons(Rule::part1()), [(1111, repeated(11, 2, 2))]);
+        assert_eq!(reasons(Rule::part2()), [(1111, repeated(1, 1, 4))]);
+        assert_eq!(
+            reasons(Rule::new(Pattern::Palindrome).unwrap()),
+            [(1111, super::Reason::Palindrome)]
+        );
+    }
+}
@@ -0,0 +1,207 @@
+mod error;
+mod invalid;
+mod range;
+mod rules;
+
+pub use error::{ParseError, ParseErrorKind};
+pub use invalid::{InvalidId, InvalidIds, Reason};
+pub use range::IdRange;
+pub use rules::{Pattern, Rule};
+
+use anyhow::bail;
+use aoc_common::{Answers, Timer};
+use std::io::Write;
+
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    solve_with(input, &Rule::part1(), Some(&Rule::part2()), false)
+}
+
+/// Like `solve`, but with overlapping ranges merged first so an ID in several ranges only
+/// counts once
+pub fn solve_merged(input: &str) -> anyhow::Result<Answers> {
+    solve_with(input, &Rule::part1(), Some(&Rule::part2()), true)
+}
+
+/// The sums of the IDs invalid under `part1` and, if there is one, `part2`, with the ranges read
+/// in the base the two rules share and merged first if `merge` is set
+pub fn solve_with(
+    input: &str,
+    part1: &Rule,
+    part2: Option<&Rule>,
+    merge: bool,
+) -> anyhow::Result<Answers> {
+    if part2.is_some_and(|rule| rule.base() != part1.base()) {
+        bail!("Both parts have to read IDs in the same base");
+    }
+
+    let mut timer = Timer::start();
+    let mut ranges = IdRange::parse_all_in(input, part1)?;
+    if merge {
+        ranges = IdRange::merge(ranges);
+    }
+    timer.lap("parse");
+
+    let part1 = invalid_sum(&ranges, part1);
+    timer.lap("part1");
+
+    let part2 = part2.map(|rule| invalid_sum(&ranges, rule));
+    timer.lap("part2");
+
+    let answers = match part2 {
+        Some(part2) => Answers::new(part1, part2),
+        None => Answers::part1_only(part1),
+    };
+    Ok(answers.with_timings(timer))
+}
+
+/// Writes out every ID invalid under `rule`, range by range, each with the block that makes it
+/// invalid, and how many there were in each range along with their sum. The ranges, IDs and
+/// blocks are read and written in the rule's base, the counts and sums in decimal.
+pub fn list(input: &str, rule: &Rule, merge: bool, out: &mut impl Write) -> anyhow::Result<()> {
+    let mut ranges = IdRange::parse_all_in(input, rule)?;
+    if merge {
+        ranges = IdRange::merge(ranges);
+    }
+
+    let (mut count, mut sum) = (0, 0);
+    for range in ranges {
+        writeln!(out, "{}-{}", rule.format(range.lo), rule.format(range.hi))?;
+        let (mut range_count, mut range_sum) = (0, 0);
+        for invalid in rule.invalid_ids(range.lo, range.hi) {
+            let id = rule.format(invalid.id);
+            match invalid.reason {
+                Reason::Repeated { block, repeats, .. } => {
+                    writeln!(out, "  {id}  block {} x{repeats}", rule.format(block))?
+                }
+                Reason::Palindrome => writeln!(out, "  {id}  palindrome")?,
+            }
+            range_count += 1;
+            range_sum += invalid.id as u128;
+        }
+        writeln!(out, "  {range_count} invalid, sum {range_sum}")?;
+        count += range_count;
+        sum += range_sum;
+    }
+    writeln!(out, "Total: {count} invalid, sum {sum}")?;
+
+    Ok(())
+}
+
+// The sum of the IDs in all the ranges that are invalid under `rule`
+fn invalid_sum(ranges: &[IdRange], rule: &Rule) -> u128 {
+    ranges
+        .iter()
+        .map(|range| rule.sum(range.lo, range.hi))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The digit by digit check the sums replaced, less its habit of counting odd lengths like 111
+    // as two copies of a block
+    fn by_strings(lo: u64, hi: u64) -> (u128, u128) {
+        let mut part1 = 0;
+        let mut part2 = 0;
+        for num in lo..=hi {
+            let chars = num.to_string().chars().collect::<Vec<_>>();
+            for i in (1..chars.len()).rev() {
+                let chunks = chars.chunks(i).collect::<Vec<_>>();
+                if chunks.iter().skip(1).all(|c| *c == chunks[0]) {
+                    if chars.len() % 2 == 0 && i == chars.len() / 2 {
+                        part1 += num as u128;
+                    }
+                    part2 += num as u128;
+                    break;
+                }
+            }
+        }
+
+        (part1, part2)
+    }
+
+    #[test]
+    fn test_against_strings() {
+        for (lo, hi) in [
+            (1, 99_999),
+            (95, 115),
+            (987_000, 1_002_000),
+            (111_111, 111_111),
+        ] {
+            let ranges = [IdRange { lo, hi }];
+            assert_eq!(
+                (
+                    invalid_sum(&ranges, &Rule::part1()),
+                    invalid_sum(&ranges, &Rule::part2())
+                ),
+                by_strings(lo, hi),
+                "{lo}-{hi}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_wide_range() {
+        // Every 12 digit number, too many to check one by one: the blocks of 6 digits repeated
+        // twice sum to 1000001 times the sum of the blocks
+        let blocks = (100_000 + 999_999) * 900_000 / 2;
+        let twelve_digits = [IdRange {
+            lo: 100_000_000_000,
+            hi: 999_999_999_999,
+        }];
+        assert_eq!(
+            invalid_sum(&twelve_digits, &Rule::part1()),
+            1_000_001 * blocks
+        );
+        let everything = [IdRange {
+            lo: 1,
+            hi: u64::MAX,
+        }];
+        assert!(invalid_sum(&everything, &Rule::part2()) > 0);
+    }
+
+    #[test]
+    fn test_merged() {
+        // 11 and 22 are in both ranges
+        let input = "1-30,11-22\n";
+        assert_eq!(solve(input).unwrap().part1.unwrap(), "66");
+        assert_eq!(solve_merged(input).unwrap().part1.unwrap(), "33");
+        assert!(solve("1-30,x").is_err());
+    }
+
+    #[test]
+    fn test_hex() {
+        // 0x10 to 0x30 holds two copies of 1 and of 2, and 0xaaa is three copies of a
+        let hex = |pattern| Rule::new(pattern).unwrap().in_base(16).unwrap();
+        let (part1, part2) = (hex(Pattern::Repeats(2)), hex(Pattern::AtLeastRepeats(2)));
+        let answers = solve_with("10-30,aaa-aaa\n", &part1, Some(&part2), false).unwrap();
+        assert_eq!(answers.part1.unwrap(), (0x11 + 0x22).to_string());
+        assert_eq!(answers.part2.unwrap(), (0x11 + 0x22 + 0xaaa).to_string());
+
+        let palindromes = solve_with("f0-fff", &hex(Pattern::Palindrome), None, false).unwrap();
+        assert_eq!(palindromes.part2, None);
+        assert!(solve_with("10-30", &part1, Some(&Rule::part2()), false).is_err());
+        assert!(solve("10-3a").is_err());
+
+        let mut out = Vec::new();
+        list("a0-bb", &part1, false, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "a0-bb\n  aa  block a x2\n  bb  block b x2\n  2 invalid, sum 357\n\
+             Total: 2 invalid, sum 357\n"
+        );
+    }
+
+    #[test]
+    fn test_list() {
+        let mut out = Vec::new();
+        list("95-115,1010-1011", &Rule::part2(), false, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "95-115\n  99  block 9 x2\n  111  block 1 x3\n  2 invalid, sum 210\n\
+             1010-1011\n  1010  block 10 x2\n  1 invalid, sum 1010\n\
+             Total: 3 invalid, sum 1220\n"
+        );
+    }
+}
@@ -1,99 +1,47 @@
-use std::fs::File;
-use std::io::BufRead;
-use std::path::PathBuf;
+use anyhow::{Context, bail};
+use aoc_common::Cli;
+use day02::{Pattern, Rule};
 
 fn main() -> anyhow::Result<()> {
-    let args = std::env::args().collect::<Vec<_>>();
-    let dir = if args.contains(&String::from("--test_input")) {
-        PathBuf::from("test_input")
-    } else {
-        PathBuf::from("input")
+    // `--merge` joins overlapping ranges first so each ID counts once, and `--list [--part 1|2]`
+    // prints the invalid IDs under one part's rule, part 2 by default, instead of the sums.
+    // `--pattern repeats=K|at-least=K|block=N|palindrome` swaps the parts' rules for one of
+    // its own, and `--base B` reads and writes the IDs in base B.
+    let cli = Cli::from_env()?;
+    let base = match cli.value("--base")? {
+        Some(base) => base
+            .parse::<u32>()
+            .with_context(|| format!("Invalid base {base}"))?,
+        None => 10,
     };
-    let f = File::open(dir.join("day02.txt"))?;
-    let reader = std::io::BufReader::new(f);
-    let lines = reader.lines();
-
-    let mut ranges = Vec::new();
-    for line in lines {
-        let Ok(line) = line else {
-            continue;
+    let pattern = match cli.value("--pattern")? {
+        Some(pattern) => Some(Rule::new(pattern.parse::<Pattern>()?)?.in_base(base)?),
+        None => None,
+    };
+    let merge = cli.flag("--merge");
+
+    if cli.flag("--list") {
+        let rule = match (pattern, cli.value("--part")?) {
+            (Some(rule), None) => rule,
+            (Some(_), Some(_)) => bail!("--pattern and --part pick the rule two ways"),
+            (None, Some("1")) => Rule::part1().in_base(base)?,
+            (None, Some("2") | None) => Rule::part2().in_base(base)?,
+            (None, Some(part)) => bail!("There is no part {part}"),
         };
-        if line.is_empty() {
-            continue;
-        }
-        ranges.extend(line.split(',').filter(|s| !s.is_empty()).map(|r| {
-            r.split('-')
-                .map(|n| n.parse::<usize>().unwrap())
-                .collect::<Vec<_>>()
-        }));
-    }
-
-    // let part1_result = part1(&ranges);
-    // let part2_result = part2(&ranges);
-    // println!("Part 1: {part1_result}");
-    // println!("Part 2: {part2_result}");
-    both_parts(&ranges);
-
-    Ok(())
-}
-
-fn part1(ranges: &[Vec<usize>]) -> usize {
-    let mut result = 0;
-    for range in ranges {
-        for num in range[0]..=range[1] {
-            let num_string = num.to_string();
-            let midpoint = num_string.len() / 2;
-
-            if num_string[..midpoint] == num_string[midpoint..] {
-                result += num;
-            }
-        }
-    }
-
-    result
-}
-
-fn part2(ranges: &[Vec<usize>]) -> usize {
-    let mut result = 0;
-
-    for range in ranges {
-        for num in range[0]..=range[1] {
-            let chars = num.to_string().chars().collect::<Vec<_>>();
-            for i in (1..chars.len()).rev() {
-                let chunks = chars.chunks(i).collect::<Vec<_>>();
-                if chunks.iter().skip(1).all(|c| *c == chunks[0]) {
-                    result += num;
-                    break;
-                }
-            }
-        }
+        let input = cli.read_input(2)?;
+        return day02::list(&input, &rule, merge, &mut std::io::stdout().lock());
     }
 
-    result
-}
-
-fn both_parts(ranges: &[Vec<usize>]) {
-    let mut part1 = 0;
-    let mut part2 = 0;
-
-    for range in ranges {
-        for num in range[0]..=range[1] {
-            let num_string = num.to_string();
-            let midpoint = num_string.len() / 2;
+    // A pattern of its own has just the one sum
+    let (part1, part2) = match pattern {
+        Some(rule) => (rule, None),
+        None => (
+            Rule::part1().in_base(base)?,
+            Some(Rule::part2().in_base(base)?),
+        ),
+    };
 
-            let chars = num_string.chars().collect::<Vec<_>>();
-            for i in (1..chars.len()).rev() {
-                let chunks = chars.chunks(i).collect::<Vec<_>>();
-                if chunks.iter().skip(1).all(|c| *c == chunks[0]) {
-                    if i == midpoint {
-                        part1 += num;
-                    }
-                    part2 += num;
-                    break;
-                }
-            }
-        }
-    }
-    println!("Part 1: {part1}");
-    println!("Part 2: {part2}");
+    aoc_common::run(2, |input| {
+        day02::solve_with(input, &part1, part2.as_ref(), merge)
+    })
 }
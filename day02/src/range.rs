@@ -0,0 +1,164 @@
+use crate::error::{ParseError, ParseErrorKind};
+use crate::rules::Rule;
+
+/// The IDs from `lo` to `hi`, both included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IdRange {
+    pub lo: u64,
+    pub hi: u64,
+}
+
+impl IdRange {
+    /// Every range in a comma or whitespace separated list like `11-22,95-115`
+    pub fn parse_all(input: &str) -> Result<Vec<Self>, ParseError> {
+        Self::parse_all_in(input, &Rule::part1())
+    }
+
+    /// Like `parse_all`, with the ends written in the base `rule` reads IDs in, so `a-1f` is a
+    /// range of hex IDs
+    pub fn parse_all_in(input: &str, rule: &Rule) -> Result<Vec<Self>, ParseError> {
+        let mut ranges = Vec::new();
+        let mut offset = 0;
+        // The separators are all one byte long
+        for token in input.split(|c: char| c == ',' || c.is_ascii_whitespace()) {
+            if !token.is_empty() {
+                ranges.push(Self::parse(token, offset, rule.base())?);
+            }
+            offset += token.len() + 1;
+        }
+
+        Ok(ranges)
+    }
+
+    // `base` is one a `Rule` allows, between 2 and 36
+    fn parse(token: &str, offset: usize, base: u32) -> Result<Self, ParseError> {
+        let error = |kind| ParseError {
+            token: token.to_string(),
+            offset,
+            kind,
+        };
+        // Digits only, where `from_str_radix` would also take a leading `+`
+        let number = |s: &str| {
+            u64::from_str_radix(s, base)
+                .ok()
+                .filter(|_| s.chars().all(|c| c.is_digit(base)))
+                .ok_or_else(|| error(ParseErrorKind::BadNumber(s.to_string())))
+        };
+
+        let (lo, hi) = token
+            .split_once('-')
+            .ok_or_else(|| error(ParseErrorKind::Malformed))?;
+        let (lo, hi) = (number(lo)?, number(hi)?);
+        if lo > hi {
+            return Err(error(ParseErrorKind::Reversed { lo, hi }));
+        }
+
+        Ok(Self { lo, hi })
+    }
+
+    /// The same IDs with every ID in exactly one range, sorted, with overlapping and touching
+    /// ranges joined into one
+    pub fn merge(mut ranges: Vec<Self>) -> Vec<Self> {
+        ranges.sort();
+        let mut merged: Vec<Self> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.lo <= last.hi.saturating_add(1) => {
+                    last.hi = last.hi.max(range.hi)
+                }
+                _ => merged.push(range),
+            }
+        }
+
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            IdRange::parse_all("11-22,95-115,\n998-1012\n").unwrap(),
+            [
+                IdRange { lo: 11, hi: 22 },
+                IdRange { lo: 95, hi: 115 },
+                IdRange { lo: 998, hi: 1012 }
+            ]
+        );
+
+        let error = |input| IdRange::parse_all(input).unwrap_err();
+        assert_eq!(
+            error("11-22,95+115"),
+            ParseError {
+                token: "95+115".to_string(),
+                offset: 6,
+                kind: ParseErrorKind::Malformed
+            }
+        );
+        assert_eq!(
+            error("1-2\n3-x4").kind,
+            ParseErrorKind::BadNumber("x4".to_string())
+        );
+        assert_eq!(error("1-2\n3-x4").offset, 4);
+        assert_eq!(
+            error("1-+2").kind,
+            ParseErrorKind::BadNumber("+2".to_string())
+        );
+        assert_eq!(
+            error("1-99999999999999999999").kind,
+            ParseErrorKind::BadNumber("99999999999999999999".to_string())
+        );
+        assert_eq!(
+            error("5-6,22-11").to_string(),
+            "range \"22-11\" at byte 4: 22 is greater than 11"
+        );
+    }
+
+    #[test]
+    fn test_parse_in_base() {
+        let hex = Rule::part1().in_base(16).unwrap();
+        assert_eq!(
+            IdRange::parse_all_in("a-1F,10-ff", &hex).unwrap(),
+            [IdRange { lo: 10, hi: 31 }, IdRange { lo: 16, hi: 255 }]
+        );
+        assert_eq!(
+            IdRange::parse_all("a-1f").unwrap_err().kind,
+            ParseErrorKind::BadNumber("a".to_string())
+        );
+
+        let binary = Rule::part1().in_base(2).unwrap();
+        assert_eq!(
+            IdRange::parse_all_in("101-2", &binary).unwrap_err().kind,
+            ParseErrorKind::BadNumber("2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let ranges = IdRange::parse_all("30-40,1-10,5-12,13-14,35-36,50-60").unwrap();
+        assert_eq!(
+            IdRange::merge(ranges),
+            [
+                IdRange { lo: 1, hi: 14 },
+                IdRange { lo: 30, hi: 40 },
+                IdRange { lo: 50, hi: 60 }
+            ]
+        );
+        assert_eq!(
+            IdRange::merge(vec![
+                IdRange {
+                    lo: 0,
+                    hi: u64::MAX
+                },
+                IdRange { lo: 7, hi: 9 }
+            ]),
+            [IdRange {
+                lo: 0,
+                hi: u64::MAX
+            }]
+        );
+    }
+}
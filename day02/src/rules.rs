@@ -0,0 +1,394 @@
+// Which IDs are invalid, and the sum of the invalid IDs in a range. The sums are worked out a
+// digit length at a time from the shape of the digits rather than by checking every ID, so a range
+// spanning billions of IDs costs no more than a short one.
+
+use crate::invalid::InvalidIds;
+use anyhow::{Context, bail};
+use std::str::FromStr;
+
+// Digits in every base up to 36
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// What the digits of an invalid ID look like
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pattern {
+    /// Some block repeated exactly this many times, so 1111 is two copies of 11 as well as four of 1
+    Repeats(u32),
+    /// Some block repeated this many times or more
+    AtLeastRepeats(u32),
+    /// A block of this many digits repeated at least twice
+    BlockLength(u32),
+    /// At least two digits that read the same backwards
+    Palindrome,
+}
+
+// As written on the command line: `repeats=K`, `at-least=K`, `block=N` or `palindrome`
+impl FromStr for Pattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        if s == "palindrome" {
+            return Ok(Pattern::Palindrome);
+        }
+
+        let (name, n) = s
+            .split_once('=')
+            .with_context(|| format!("Unknown pattern {s}"))?;
+        let n = n
+            .parse::<u32>()
+            .with_context(|| format!("Invalid number {n} in pattern {s}"))?;
+        match name {
+            "repeats" => Ok(Pattern::Repeats(n)),
+            "at-least" => Ok(Pattern::AtLeastRepeats(n)),
+            "block" => Ok(Pattern::BlockLength(n)),
+            _ => {
+                bail!("Unknown pattern {s}, expected repeats=K, at-least=K, block=N or palindrome")
+            }
+        }
+    }
+}
+
+/// A pattern for the digits of IDs written in some base
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pattern: Pattern,
+    base: u32,
+}
+
+impl Rule {
+    /// Decimal IDs matching `pattern`
+    pub fn new(pattern: Pattern) -> anyhow::Result<Self> {
+        match pattern {
+            Pattern::Repeats(k) | Pattern::AtLeastRepeats(k) if k < 2 => {
+                bail!("A block has to be repeated at least twice, not {k} times")
+            }
+            Pattern::BlockLength(0) => bail!("A block needs at least one digit"),
+            _ => Ok(Self { pattern, base: 10 }),
+        }
+    }
+
+    /// Part 1: IDs that are two copies of a block
+    pub fn part1() -> Self {
+        Self {
+            pattern: Pattern::Repeats(2),
+            base: 10,
+        }
+    }
+
+    /// Part 2: IDs that are two or more copies of a block
+    pub fn part2() -> Self {
+        Self {
+            pattern: Pattern::AtLeastRepeats(2),
+            base: 10,
+        }
+    }
+
+    /// The same pattern for IDs written in `base`, which goes up to 36 so every digit has a
+    /// character of its own
+    pub fn in_base(self, base: u32) -> anyhow::Result<Self> {
+        if !(2..=36).contains(&base) {
+            bail!("Base {base} is not between 2 and 36");
+        }
+
+        Ok(Self { base, ..self })
+    }
+
+    pub fn pattern(&self) -> Pattern {
+        self.pattern
+    }
+
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// `id` written in this rule's base, with letters for the digits past 9
+    pub fn format(&self, id: u64) -> String {
+        self.digits(id)
+            .into_iter()
+            .map(|d| DIGITS[d as usize] as char)
+            .collect()
+    }
+
+    /// Whether `id` is invalid under this rule
+    pub fn matches(&self, id: u64) -> bool {
+        let digits = self.digits(id);
+        let len = digits.len();
+        let repeats = |period: usize| {
+            period < len
+                && len.is_multiple_of(period)
+                && digits.chunks(period).all(|c| c == &digits[..period])
+        };
+
+        match self.pattern {
+            Pattern::Repeats(k) => len.is_multiple_of(k as usize) && repeats(len / k as usize),
+            Pattern::AtLeastRepeats(k) => (1..=len / k as usize).any(repeats),
+            Pattern::BlockLength(period) => repeats(period as usize),
+            Pattern::Palindrome => len > 1 && digits.iter().eq(digits.iter().rev()),
+        }
+    }
+
+    /// The sum of the invalid IDs in `lo..=hi`
+    pub fn sum(&self, lo: u64, hi: u64) -> u128 {
+        self.lengths(lo, hi)
+            .map(|(len, lo, hi)| match self.pattern {
+                Pattern::AtLeastRepeats(k) => self
+                    .shortest_period_sums(lo, hi, len)
+                    .into_iter()
+                    .filter(|&(period, _)| len / period >= k)
+                    .map(|(_, sum)| sum)
+                    .sum(),
+                Pattern::Palindrome => self.palindrome_sum(lo, hi, len),
+                _ => self
+                    .periods(len)
+                    .into_iter()
+                    .map(|period| self.repeated_sum(lo, hi, len, period))
+                    .sum(),
+            })
+            .sum()
+    }
+
+    /// The invalid IDs in `lo..=hi` in increasing order, found the same way as the sums so the
+    /// valid IDs in between are never looked at
+    pub fn invalid_ids(&self, lo: u64, hi: u64) -> InvalidIds {
+        InvalidIds::new(*self, lo, hi)
+    }
+
+    // The part of `lo..=hi` with each number of digits, from two up
+    pub(crate) fn lengths(&self, lo: u64, hi: u64) -> impl Iterator<Item = (u32, u128, u128)> {
+        let base = self.base as u128;
+        (2..=self.digits(u64::MAX).len() as u32)
+            .map(move |len| {
+                let lo = (lo as u128).max(base.pow(len - 1));
+                let hi = (hi as u128).min(base.pow(len) - 1);
+                (len, lo, hi)
+            })
+            .filter(|&(_, lo, hi)| lo <= hi)
+    }
+
+    // The lengths of the blocks a `len` digit number can repeat to match a repeating pattern.
+    // Any number of digits can repeat a block of each, so these overlap for `AtLeastRepeats`.
+    pub(crate) fn periods(&self, len: u32) -> Vec<u32> {
+        (1..len)
+            .filter(|&p| len.is_multiple_of(p))
+            .filter(|&p| match self.pattern {
+                Pattern::Repeats(k) => len / p == k,
+                Pattern::AtLeastRepeats(k) => len / p >= k,
+                Pattern::BlockLength(period) => p == period,
+                Pattern::Palindrome => false,
+            })
+            .collect()
+    }
+
+    // The length of the shortest block `id` is a repeat of, which is all of it if none is
+    pub(crate) fn shortest_period(&self, id: u64) -> usize {
+        let digits = self.digits(id);
+        let len = digits.len();
+        (1..len)
+            .find(|&p| len.is_multiple_of(p) && digits.chunks(p).all(|c| c == &digits[..p]))
+            .unwrap_or(len)
+    }
+
+    // Most significant first
+    fn digits(&self, mut id: u64) -> Vec<u64> {
+        let mut digits = Vec::new();
+        loop {
+            digits.push(id % self.base as u64);
+            id /= self.base as u64;
+            if id == 0 {
+                break;
+            }
+        }
+        digits.reverse();
+
+        digits
+    }
+
+    // The numbers with `len` digits made of a block of `period` digits repeated to fill them are
+    // the blocks times 1 followed by `period - 1` zeros, repeated `len / period` times. That
+    // multiplier, and the first and last blocks whose multiple lands in `lo..=hi`, if any do.
+    pub(crate) fn blocks(
+        &self,
+        lo: u128,
+        hi: u128,
+        len: u32,
+        period: u32,
+    ) -> Option<(u128, u128, u128)> {
+        let base = self.base as u128;
+        let multiplier = (base.pow(len) - 1) / (base.pow(period) - 1);
+        let first = base.pow(period - 1).max(lo.div_ceil(multiplier));
+        let last = (base.pow(period) - 1).min(hi / multiplier);
+
+        (first <= last).then_some((multiplier, first, last))
+    }
+
+    // An arithmetic series over the blocks
+    fn repeated_sum(&self, lo: u128, hi: u128, len: u32, period: u32) -> u128 {
+        self.blocks(lo, hi, len, period)
+            .map_or(0, |(multiplier, first, last)| {
+                multiplier * (first + last) * (last - first + 1) / 2
+            })
+    }
+
+    // For each period dividing `len`, the sum of the numbers whose shortest period it is. A
+    // number repeating a block also repeats every block whose length is a multiple of its
+    // shortest one, so working up through the divisors, the sum for a period less the sums
+    // already found for its own divisors leaves the numbers for which it is the shortest.
+    fn shortest_period_sums(&self, lo: u128, hi: u128, len: u32) -> Vec<(u32, u128)> {
+        let mut sums: Vec<(u32, u128)> = Vec::new();
+        for period in (1..len).filter(|&p| len.is_multiple_of(p)) {
+            let longer = sums
+                .iter()
+                .filter(|&&(p, _)| period.is_multiple_of(p))
+                .map(|&(_, sum)| sum)
+                .sum::<u128>();
+            sums.push((period, self.repeated_sum(lo, hi, len, period) - longer));
+        }
+
+        sums
+    }
+
+    // A palindrome is set by its first half, rounded up, and grows with it, so the `len` digit
+    // ones in `lo..=hi` come from a run of halves. The first and last of them, if there are any.
+    pub(crate) fn halves(&self, lo: u128, hi: u128, len: u32) -> Option<(u128, u128)> {
+        let shift = (self.base as u128).pow(len / 2);
+
+        let mut first = lo / shift;
+        if self.palindrome(first, len) < lo {
+            first += 1;
+        }
+        let mut last = hi / shift;
+        if self.palindrome(last, len) > hi {
+            last -= 1;
+        }
+
+        (first <= last).then_some((first, last))
+    }
+
+    // Each digit of the half lands in the palindrome at its own place and at the mirrored one,
+    // which makes the sum a weighted sum of how much each digit place adds up to over the halves
+    fn palindrome_sum(&self, lo: u128, hi: u128, len: u32) -> u128 {
+        let base = self.base as u128;
+        let half = len.div_ceil(2);
+        let Some((first, last)) = self.halves(lo, hi, len) else {
+            return 0;
+        };
+
+        (0..half)
+            .map(|place| {
+                let (high, low) = (len - half + place, half - 1 - place);
+                let weight = if high == low {
+                    base.pow(high)
+                } else {
+                    base.pow(high) + base.pow(low)
+                };
+                weight * (self.digit_sum(last, place) - self.digit_sum(first - 1, place))
+            })
+            .sum()
+    }
+
+    // The `len` digit palindrome starting with the digits of `half`
+    pub(crate) fn palindrome(&self, half: u128, len: u32) -> u128 {
+        let base = self.base as u128;
+        let mut value = half;
+        let mut rest = if len % 2 == 1 { half / base } else { half };
+        while rest > 0 {
+            value = value * base + rest % base;
+            rest /= base;
+        }
+
+        value
+    }
+
+    // The sum of the digits at `place` over every number in `0..=n`. They run through every digit
+    // `base.pow(place)` times a go, with a partial cycle at the end.
+    fn digit_sum(&self, n: u128, place: u32) -> u128 {
+        let base = self.base as u128;
+        let step = base.pow(place);
+        let cycle = step * base;
+        let count = n + 1;
+        let (cycles, rest) = (count / cycle, count % cycle);
+        let (digit, extra) = (rest / step, rest % step);
+
+        cycles * step * base * (base - 1) / 2
+            + step * digit * digit.saturating_sub(1) / 2
+            + digit * extra
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sums_against_matches() {
+        let patterns = [
+            Pattern::Repeats(2),
+            Pattern::Repeats(3),
+            Pattern::AtLeastRepeats(2),
+            Pattern::AtLeastRepeats(3),
+            Pattern::BlockLength(1),
+            Pattern::BlockLength(2),
+            Pattern::Palindrome,
+        ];
+        for base in [2, 3, 10, 16] {
+            for pattern in patterns {
+                let rule = Rule::new(pattern).unwrap().in_base(base).unwrap();
+                for (lo, hi) in [(0, 5000), (4095, 4097), (300, 310), (65_000, 70_000)] {
+                    let expected = (lo..=hi)
+                        .filter(|&id| rule.matches(id))
+                        .map(u128::from)
+                        .sum::<u128>();
+                    assert_eq!(rule.sum(lo, hi), expected, "{rule:?} {lo}-{hi}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let hex = |pattern| Rule::new(pattern).unwrap().in_base(16).unwrap();
+        assert!(hex(Pattern::Repeats(2)).matches(0xabab));
+        assert!(!hex(Pattern::Repeats(2)).matches(0xaba));
+        assert!(hex(Pattern::Palindrome).matches(0xaba));
+        assert!(hex(Pattern::AtLeastRepeats(3)).matches(0x777777));
+        assert!(!hex(Pattern::BlockLength(2)).matches(0x777));
+
+        assert!(Rule::part1().matches(1111));
+        assert!(!Rule::part1().matches(111));
+        assert!(Rule::part2().matches(111));
+        assert!(!Rule::new(Pattern::Palindrome).unwrap().matches(7));
+    }
+
+    #[test]
+    fn test_patterns() {
+        assert_eq!("repeats=3".parse::<Pattern>().unwrap(), Pattern::Repeats(3));
+        assert_eq!(
+            "at-least=2".parse::<Pattern>().unwrap(),
+            Pattern::AtLeastRepeats(2)
+        );
+        assert_eq!(
+            "block=4".parse::<Pattern>().unwrap(),
+            Pattern::BlockLength(4)
+        );
+        assert_eq!(
+            "palindrome".parse::<Pattern>().unwrap(),
+            Pattern::Palindrome
+        );
+        assert!("repeats".parse::<Pattern>().is_err());
+        assert!("block=x".parse::<Pattern>().is_err());
+        assert!("twice=2".parse::<Pattern>().is_err());
+
+        let hex = Rule::part1().in_base(16).unwrap();
+        assert_eq!(hex.format(0xabab), "abab");
+        assert_eq!(Rule::part1().format(0), "0");
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(Rule::new(Pattern::Repeats(1)).is_err());
+        assert!(Rule::new(Pattern::AtLeastRepeats(0)).is_err());
+        assert!(Rule::new(Pattern::BlockLength(0)).is_err());
+        assert!(Rule::part1().in_base(1).is_err());
+        assert!(Rule::part1().in_base(37).is_err());
+    }
+}
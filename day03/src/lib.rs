@@ -0,0 +1,208 @@
+use anyhow::{Context, bail};
+use aoc_common::{Answers, Timer};
+use num_bigint::BigUint;
+
+// How many batteries each part turns on in a bank
+const PARTS: [usize; 2] = [2, 12];
+
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let mut timer = Timer::start();
+    let banks = banks(input)?;
+    timer.lap("parse");
+
+    let part1_result = total_joltage(&banks, PARTS[0])?;
+    timer.lap("part1");
+
+    let part2_result = total_joltage(&banks, PARTS[1])?;
+    timer.lap("part2");
+
+    Ok(Answers::new(part1_result, part2_result).with_timings(timer))
+}
+
+/// `solve` with every bank's choices from `explain` as its report
+pub fn solve_explained(input: &str) -> anyhow::Result<Answers> {
+    Ok(solve(input)?.with_report(explain(input)?))
+}
+
+/// Every bank with the batteries each part turns on marked underneath it
+pub fn explain(input: &str) -> anyhow::Result<String> {
+    let mut report = String::new();
+    for (i, nums) in banks(input)?.iter().enumerate() {
+        let bank = nums.iter().map(|n| n.to_string()).collect::<String>();
+        report += &format!("{:<9}{bank}\n", format!("Bank {}", i + 1));
+        for (part, n) in PARTS.into_iter().enumerate() {
+            let choice = find_highest_num(nums, n)?;
+            let mut marks = vec![' '; nums.len()];
+            choice.indices.iter().for_each(|&i| marks[i] = '^');
+            report += &format!(
+                "  Part {} {}  {}\n",
+                part + 1,
+                marks.into_iter().collect::<String>(),
+                choice.digits
+            );
+        }
+    }
+
+    Ok(report)
+}
+
+// The digits of each bank, one bank per line. Blank lines are skipped.
+fn banks(input: &str) -> anyhow::Result<Vec<Vec<u32>>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            line.trim()
+                .chars()
+                .map(|c| c.to_digit(10))
+                .collect::<Option<Vec<_>>>()
+                .with_context(|| format!("Line {}: {line} is not all digits", i + 1))
+        })
+        .collect()
+}
+
+fn total_joltage(banks: &[Vec<u32>], n: usize) -> anyhow::Result<BigUint> {
+    banks.iter().try_fold(BigUint::ZERO, |total, nums| {
+        Ok(total + find_highest_num(nums, n)?.value())
+    })
+}
+
+/// The batteries picked from a bank, by index, and the joltage their digits make
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    pub indices: Vec<usize>,
+    pub digits: String,
+}
+
+impl Choice {
+    pub fn value(&self) -> BigUint {
+        // No digits at all make 0
+        BigUint::parse_bytes(self.digits.as_bytes(), 10).unwrap_or_default()
+    }
+}
+
+/// The largest number made of `n` of the digits in their original order. A digit is worth
+/// dropping whenever a bigger one comes after it, as long as there are digits left to drop, so
+/// keeping the ones picked so far on a stack and popping the smaller ones off as bigger ones come
+/// along leaves the best choice in one pass.
+pub fn find_highest_num(nums: &[u32], n: usize) -> anyhow::Result<Choice> {
+    if n > nums.len() {
+        bail!("Looking for {n} digits but only have {}", nums.len());
+    }
+
+    let mut drops = nums.len() - n;
+    let mut picked: Vec<usize> = Vec::with_capacity(nums.len());
+    for (i, &num) in nums.iter().enumerate() {
+        while drops > 0 && picked.last().is_some_and(|&last| nums[last] < num) {
+            picked.pop();
+            drops -= 1;
+        }
+        picked.push(i);
+    }
+    picked.truncate(n);
+
+    let digits = picked
+        .iter()
+        .map(|&i| char::from_digit(nums[i], 10).context("Batteries are rated 0 to 9"))
+        .collect::<anyhow::Result<String>>()?;
+
+    Ok(Choice {
+        indices: picked,
+        digits,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // The recursive search the stack replaced, rescanning what's left for each digit
+    fn find_highest_num_recursive(nums: &[u32], n: usize) -> usize {
+        if n > nums.len() {
+            panic!("Looking for {n} digits but only have {}", nums.len());
+        }
+        match n {
+            0 => return 0,
+            // Base case: pick the highest digit remaining
+            1 => return *nums.iter().max().unwrap() as usize,
+            _ => {}
+        }
+
+        // Only have enough digits left, return early
+        if n == nums.len() {
+            return nums.iter().fold(0, |acc, n| (acc * 10) + *n as usize);
+        }
+
+        // Find the highest digit that is at least n digits away from the end of the array and recurse
+        let mut highest = 0;
+        let mut index = 0;
+        for (i, &num) in nums.iter().enumerate().take(nums.len() - n + 1) {
+            if num > highest {
+                index = i;
+                highest = num;
+            }
+        }
+        find_highest_num_recursive(&nums[(index + 1)..], n - 1)
+            + (highest as usize * 10usize.pow((n - 1) as u32))
+    }
+
+    #[test]
+    fn test1() {
+        let num = [9, 8, 7, 6, 5, 4, 3, 2, 1, 1, 1, 1, 1, 1, 1];
+        assert_eq!(find_highest_num(&num, 12).unwrap().digits, "987654321111");
+    }
+
+    #[test]
+    fn test2() {
+        let num = [8, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 9];
+        let choice = find_highest_num(&num, 12).unwrap();
+        assert_eq!(choice.value(), BigUint::from(811111111119u64));
+        assert_eq!(choice.indices, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 14]);
+    }
+
+    #[test]
+    fn test_against_recursive() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..2000 {
+            let len = rng.random_range(1..=40);
+            // Few distinct digits make for lots of ties
+            let top = rng.random_range(1..=9);
+            let nums = (0..len)
+                .map(|_| rng.random_range(0..=top))
+                .collect::<Vec<_>>();
+            for n in 0..=len.min(19) {
+                assert_eq!(
+                    find_highest_num(&nums, n).unwrap().value(),
+                    BigUint::from(find_highest_num_recursive(&nums, n)),
+                    "{nums:?} {n}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_long_banks() {
+        assert!(find_highest_num(&[1, 2], 3).is_err());
+        assert_eq!(find_highest_num(&[], 0).unwrap().value(), BigUint::ZERO);
+
+        // 300 digits, far past what a u128 holds: every 9 makes it, after as few 1s as will do
+        let nums = [vec![1; 200], vec![9; 100]].concat();
+        let choice = find_highest_num(&nums, 150).unwrap();
+        assert_eq!(choice.digits, "1".repeat(50) + &"9".repeat(100));
+        assert_eq!(choice.indices, (0..50).chain(200..300).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_explain() {
+        let report = explain("818181911112111\n").unwrap();
+        assert_eq!(
+            report,
+            "Bank 1   818181911112111\n\
+             \x20 Part 1       ^    ^     92\n\
+             \x20 Part 2 ^ ^ ^ ^^^^^^^^^  888911112111\n"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use aoc_common::{Answers, Timer};
+use std::cmp::max;
+use std::collections::HashSet;
+
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let mut timer = Timer::start();
+    let mut map = Map::from_str(input)?;
+    timer.lap("parse");
+
+    let part1 = map.count_accessible().len();
+    timer.lap("part1");
+
+    let part2 = map.remove_accessible();
+    timer.lap("part2");
+
+    Ok(Answers::new(part1, part2).with_timings(timer))
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+struct Position {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct Map {
+    rolls: HashSet<Position>,
+    x_size: usize,
+    y_size: usize,
+}
+
+impl Map {
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut rolls = HashSet::new();
+        let mut x_size = 0;
+        let mut y_size = 0;
+        for (y, line) in s.lines().enumerate() {
+            y_size = max(y, y_size);
+            for (x, c) in line.chars().enumerate() {
+                x_size = max(x, x_size);
+                if c == '@' {
+                    rolls.insert(Position {
+                        x: x as i32,
+                        y: y as i32,
+                    });
+                }
+            }
+        }
+        Ok(Self {
+            rolls,
+            x_size: x_size + 1,
+            y_size: y_size + 1,
+        })
+    }
+
+    fn count_accessible(&self) -> HashSet<Position> {
+        let mut accessible = HashSet::new();
+        for roll in self.rolls.iter() {
+            let mut adjacent_count = 0;
+            for adjacent in self.get_adjacent(roll) {
+                if self.rolls.contains(&adjacent) {
+                    adjacent_count += 1;
+                }
+            }
+            if adjacent_count < 4 {
+                accessible.insert(*roll);
+            }
+        }
+
+        accessible
+    }
+
+    fn remove_accessible(&mut self) -> usize {
+        let mut result = 0;
+        loop {
+            let accessible = self.count_accessible();
+            if accessible.is_empty() {
+                break;
+            }
+            self.rolls.retain(|p| !accessible.contains(p));
+            result += accessible.len();
+        }
+
+        result
+    }
+
+
+    fn get_adjacent(&self, pos: &Position) -> Vec<Position> {
+        [
+            Position {
+                x: pos.x - 1,
+                y: pos.y,
+            },
+            Position {
+                x: pos.x,
+                y: pos.y - 1,
+            },
+            Position {
+                x: pos.x + 1,
+                y: pos.y,
+            },
+            Position {
+                x: pos.x,
+                y: pos.y + 1,
+            },
+            Position {
+                x: pos.x - 1,
+                y: pos.y - 1,
+            },
+            Position {
+                x: pos.x - 1,
+                y: pos.y + 1,
+            },
+            Position {
+                x: pos.x + 1,
+                y: pos.y - 1,
+            },
+            Position {
+                x: pos.x + 1,
+                y: pos.y + 1,
+            }
+        ]
+        .iter()
+        .filter(|&p| self.is_valid(p))
+        .copied()
+        .collect()
+    }
+
+    fn is_valid(&self, pos: &Position) -> bool {
+        pos.x >= 0 && pos.x < self.x_size as i32 && pos.y >= 0 && pos.y < self.y_size as i32
+    }
+}
@@ -0,0 +1,126 @@
+use aoc_common::{Answers, Timer};
+use std::cmp::max;
+use std::collections::{HashMap, HashSet};
+
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let mut timer = Timer::start();
+    let mut map = Map::from_str(input)?;
+    timer.lap("parse");
+
+    // One simulation answers both parts, so it counts towards part 1 and part 2 only adds up the
+    // timelines left at the end
+    while !map.step() {}
+    timer.lap("part1");
+
+    let timelines = map.beams.values().sum::<usize>();
+    timer.lap("part2");
+
+    Ok(Answers::new(map.splits, timelines).with_timings(timer))
+}
+
+#[derive(Debug, Hash, Eq, PartialEq)]
+struct Position(usize, usize);
+
+#[derive(Debug)]
+struct Map {
+    splitters: HashSet<Position>,
+    height: usize,
+    // Position -> number of timelines that  contain this position
+    beams: HashMap<Position, usize>,
+    // Second buffer
+    next_beams: HashMap<Position, usize>,
+    splits: usize,
+}
+
+impl Map {
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut height = 0;
+        let mut splitters = HashSet::new();
+
+        // beams should start with just 1 position, the starting position S
+        let mut beams = HashMap::new();
+
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let p = Position(x, y);
+
+                match c {
+                    'S' => {
+                        beams.insert(p, 1);
+                    }
+                    '^' => {
+                        splitters.insert(p);
+                    }
+                    _ => {}
+                }
+            }
+
+            height = max(height, y + 1);
+        }
+
+        Ok(Self {
+            splitters,
+            height,
+            beams,
+            next_beams: HashMap::new(),
+            splits: 0,
+        })
+    }
+
+    // Returns true if all beams have exited tachyon (simulation is done)
+    fn step(&mut self) -> bool {
+        if self.beams.iter().all(|p| p.0.1 == usize::MAX) {
+            return true;
+        }
+
+        let Map {
+            beams,
+            next_beams,
+            height,
+            ..
+        } = self;
+
+        for (beam, timelines) in beams.iter() {
+            let next_pos = Self::next_pos(beam, *height);
+            if self.splitters.contains(&next_pos) {
+                *next_beams
+                    .entry(Position(next_pos.0 - 1, next_pos.1))
+                    .or_insert(0) += timelines;
+                *next_beams
+                    .entry(Position(next_pos.0 + 1, next_pos.1))
+                    .or_insert(0) += timelines;
+                self.splits += 1;
+            } else {
+                *next_beams.entry(next_pos).or_insert(0) += timelines;
+            }
+        }
+
+        std::mem::swap(beams, next_beams);
+        next_beams.clear();
+
+        false
+    }
+
+    // If exited tachyon, set y position to usize::MAX
+    fn next_pos(pos: &Position, max_y: usize) -> Position {
+        if pos.1 >= max_y - 1 {
+            Position(pos.0, usize::MAX)
+        } else {
+            Position(pos.0, pos.1 + 1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_input() -> anyhow::Result<()> {
+        let mut map = Map::from_str(&aoc_common::read_test_input("day07.txt")?)?;
+        while !map.step() {}
+        assert_eq!(map.splits, 21);
+        assert_eq!(map.beams.values().sum::<usize>(), 40);
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt::{Display, Formatter};
+
+/// Why a machine line was rejected and where, both counted from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The `[.##.]` light pattern is missing or has something other than `.` and `#` in it
+    BadLights,
+    /// A button isn't a list of light indices like `(0,2)`
+    BadButton,
+    /// There are no buttons between the lights and the joltage requirements
+    NoButtons,
+    /// A button toggles a light the pattern doesn't have
+    ButtonOutOfRange { index: usize, lights: usize },
+    /// The joltage requirements are missing or aren't a list of counters like `{3,5,4,7}`
+    BadJoltage,
+    /// There isn't exactly one joltage requirement per light
+    JoltageLength { lights: usize, found: usize },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadLights => write!(f, "expected a light pattern like [.##.]"),
+            Self::BadButton => write!(f, "expected a button like (0,2)"),
+            Self::NoButtons => write!(f, "expected at least one button"),
+            Self::ButtonOutOfRange { index, lights } => {
+                write!(
+                    f,
+                    "button toggles light {index} but there are only {lights}"
+                )
+            }
+            Self::BadJoltage => write!(f, "expected joltage requirements like {{3,5,4,7}}"),
+            Self::JoltageLength { lights, found } => {
+                write!(f, "expected {lights} joltage requirements, found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
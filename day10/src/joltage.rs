@@ -0,0 +1,255 @@
+// Pure Rust solver for the joltage puzzle: find non-negative press counts x for the buttons so
+// that every counter reaches its target (A x = b, where A[i][j] is 1 if button j bumps counter i)
+// while pressing as few times as possible.
+//
+// The system is brought into reduced row echelon form with fraction-free elimination, so every
+// pivot variable is an exact rational function of the free ones. The free variables are then
+// searched within their bounds (no button can be pressed more often than the smallest target it
+// touches), checking each pivot row as soon as all the free variables it depends on are set.
+
+// How often to press each button, or None if the targets can't be reached
+pub(crate) fn fewest_presses(buttons: &[Vec<usize>], target: &[u16]) -> Option<Vec<usize>> {
+    let system = System::new(buttons, target)?;
+    let mut search = Search {
+        system: &system,
+        values: vec![0; system.free.len()],
+        best: None,
+    };
+    search.run(0, 0);
+
+    search.best.map(|(_, presses)| presses)
+}
+
+struct System {
+    buttons: usize,
+    // One row per pivot: pivot coefficient, coefficient of each free variable, right hand side
+    rows: Vec<Row>,
+    // Button index of each free variable, in search order
+    free: Vec<usize>,
+    // Upper bound on the presses of each free variable
+    bounds: Vec<i64>,
+}
+
+struct Row {
+    // Button index of the pivot variable
+    col: usize,
+    pivot: i64,
+    free: Vec<i64>,
+    rhs: i64,
+    // Index into `free` of the last free variable with a non-zero coefficient, if any
+    last_free: Option<usize>,
+}
+
+impl System {
+    fn new(buttons: &[Vec<usize>], target: &[u16]) -> Option<Self> {
+        let width = buttons.len();
+        let mut matrix = target
+            .iter()
+            .enumerate()
+            .map(|(i, &t)| {
+                let mut row = buttons
+                    .iter()
+                    .map(|b| b.iter().filter(|&&c| c == i).count() as i64)
+                    .collect::<Vec<_>>();
+                row.push(t as i64);
+                row
+            })
+            .collect::<Vec<_>>();
+
+        let mut pivot_cols = Vec::new();
+        let mut r = 0;
+        for col in 0..width {
+            let Some(p) = (r..matrix.len()).find(|&p| matrix[p][col] != 0) else {
+                continue;
+            };
+            matrix.swap(r, p);
+
+            let pivot_row = matrix[r].clone();
+            for (k, row) in matrix.iter_mut().enumerate() {
+                if k != r && row[col] != 0 {
+                    let (a, c) = (pivot_row[col], row[col]);
+                    for (x, p) in row.iter_mut().zip(&pivot_row) {
+                        *x = *x * a - p * c;
+                    }
+                    normalize(row);
+                }
+            }
+            normalize(&mut matrix[r]);
+
+            pivot_cols.push(col);
+            r += 1;
+        }
+
+        // Rows below the pivots have no coefficients left, so they must also have nothing left to
+        // reach
+        if matrix[r..].iter().any(|row| row[width] != 0) {
+            return None;
+        }
+
+        let free = (0..width)
+            .filter(|c| !pivot_cols.contains(c))
+            .collect::<Vec<_>>();
+        let bounds = free
+            .iter()
+            .map(|&j| {
+                buttons[j]
+                    .iter()
+                    .filter_map(|&c| target.get(c))
+                    .min()
+                    .map_or(0, |&t| t as i64)
+            })
+            .collect();
+
+        let rows = pivot_cols
+            .iter()
+            .zip(&matrix)
+            .map(|(&col, row)| {
+                let free_coeffs = free.iter().map(|&j| row[j]).collect::<Vec<_>>();
+                Row {
+                    col,
+                    pivot: row[col],
+                    last_free: free_coeffs.iter().rposition(|&c| c != 0),
+                    free: free_coeffs,
+                    rhs: row[width],
+                }
+            })
+            .collect();
+
+        Some(Self {
+            buttons: width,
+            rows,
+            free,
+            bounds,
+        })
+    }
+}
+
+impl Row {
+    // Presses of this row's pivot button given the free presses, None if not a whole non-negative
+    // number
+    fn solve(&self, values: &[i64]) -> Option<i64> {
+        let rest = self.rhs
+            - self
+                .free
+                .iter()
+                .zip(values)
+                .map(|(c, v)| c * v)
+                .sum::<i64>();
+        (rest % self.pivot == 0 && rest / self.pivot >= 0).then(|| rest / self.pivot)
+    }
+}
+
+struct Search<'a> {
+    system: &'a System,
+    values: Vec<i64>,
+    // Fewest presses so far and how often each button is pressed for them
+    best: Option<(usize, Vec<usize>)>,
+}
+
+impl Search<'_> {
+    fn run(&mut self, depth: usize, free_sum: i64) {
+        // Pivot presses are never negative, so the free presses alone are a lower bound
+        if self
+            .best
+            .as_ref()
+            .is_some_and(|(best, _)| free_sum as usize >= *best)
+        {
+            return;
+        }
+
+        // Rows whose free variables are all set by now can be checked early
+        let settled = |row: &&Row| match row.last_free {
+            Some(last) => depth > 0 && last == depth - 1,
+            None => depth == 0,
+        };
+        if self
+            .system
+            .rows
+            .iter()
+            .filter(settled)
+            .any(|row| row.solve(&self.values).is_none())
+        {
+            return;
+        }
+
+        if depth == self.system.free.len() {
+            let mut presses = vec![0; self.system.buttons];
+            for (&j, &v) in self.system.free.iter().zip(&self.values) {
+                presses[j] = v as usize;
+            }
+            for row in &self.system.rows {
+                let Some(v) = row.solve(&self.values) else {
+                    return;
+                };
+                presses[row.col] = v as usize;
+            }
+
+            let total = presses.iter().sum::<usize>();
+            if self.best.as_ref().is_none_or(|(best, _)| total < *best) {
+                self.best = Some((total, presses));
+            }
+            return;
+        }
+
+        for value in 0..=self.system.bounds[depth] {
+            self.values[depth] = value;
+            self.run(depth + 1, free_sum + value);
+        }
+        self.values[depth] = 0;
+    }
+}
+
+// Divides a row by the gcd of its entries and makes its leading coefficient positive
+fn normalize(row: &mut [i64]) {
+    let g = row.iter().fold(0, |acc, &x| gcd(acc, x.abs()));
+    if g > 1 {
+        row.iter_mut().for_each(|x| *x /= g);
+    }
+    if row.iter().find(|&&x| x != 0).is_some_and(|&x| x < 0) {
+        row.iter_mut().for_each(|x| *x = -*x);
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buttons(s: &[&[usize]]) -> Vec<Vec<usize>> {
+        s.iter().map(|b| b.to_vec()).collect()
+    }
+
+    fn total(presses: Option<Vec<usize>>) -> Option<usize> {
+        presses.map(|p| p.iter().sum())
+    }
+
+    #[test]
+    fn test_sample_machines() {
+        let b = buttons(&[&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]]);
+        assert_eq!(total(fewest_presses(&b, &[3, 5, 4, 7])), Some(10));
+
+        let b = buttons(&[&[0, 2, 3, 4], &[2, 3], &[0, 4], &[0, 1, 2], &[1, 2, 3, 4]]);
+        assert_eq!(
+            fewest_presses(&b, &[7, 5, 12, 7, 2]),
+            Some(vec![2, 5, 0, 5, 0])
+        );
+
+        let b = buttons(&[&[0, 1, 2, 3, 4], &[0, 3, 4], &[0, 1, 2, 4, 5], &[1, 2]]);
+        assert_eq!(total(fewest_presses(&b, &[10, 11, 11, 5, 10, 5])), Some(11));
+    }
+
+    #[test]
+    fn test_unreachable() {
+        // Both counters always move together
+        assert_eq!(fewest_presses(&buttons(&[&[0, 1]]), &[1, 2]), None);
+        // Needs half a press
+        assert_eq!(
+            fewest_presses(&buttons(&[&[0, 1], &[1, 2], &[0, 2]]), &[1, 1, 1]),
+            None
+        );
+        assert_eq!(fewest_presses(&buttons(&[&[0]]), &[0]), Some(vec![0]));
+    }
+}
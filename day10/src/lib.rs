@@ -0,0 +1,498 @@
+mod error;
+mod joltage;
+mod lights;
+
+pub use error::{ParseError, ParseErrorKind};
+
+use aoc_common::{Answers, Timer};
+use lights::BitVec;
+use regex::Regex;
+use std::fmt::{Display, Formatter};
+use std::sync::LazyLock;
+#[cfg(feature = "z3")]
+use z3::ast::Ast;
+
+static LIGHTS_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\[[.#]+]$").unwrap());
+static BUTTON_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\(\d+(?:,\d+)*\)$").unwrap());
+static JOLTAGE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\{\d+(?:,\d+)*}$").unwrap());
+
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    solve_with(input, false)
+}
+
+/// `solve` with every machine's press plan for both parts as its report, each re-applied to the
+/// machine to check it really reaches the target
+pub fn solve_explained(input: &str) -> anyhow::Result<Answers> {
+    solve_with(input, true)
+}
+
+fn solve_with(input: &str, explain: bool) -> anyhow::Result<Answers> {
+    let mut timer = Timer::start();
+    let plans = timed_plans(input, &mut timer)?;
+    let total = |presses: Option<&Presses>| presses.map_or(0, Presses::total);
+    let part1 = plans.iter().map(|p| total(p.lights.as_ref())).sum::<usize>();
+    let part2 = plans.iter().map(|p| total(p.joltage.as_ref())).sum::<usize>();
+
+    let answers = Answers::new(part1, part2).with_timings(timer);
+    Ok(match explain {
+        true => answers.with_report(report(&plans)),
+        false => answers,
+    })
+}
+
+/// A machine with the fewest presses found for each part, None where there is no solution
+pub struct Plan {
+    pub line: String,
+    pub machine: Machine,
+    pub lights: Option<Presses>,
+    pub joltage: Option<Presses>,
+}
+
+/// Every machine's press plan for both parts, in input order
+pub fn plans(input: &str) -> anyhow::Result<Vec<Plan>> {
+    timed_plans(input, &mut Timer::start())
+}
+
+/// The report `solve_explained` adds to the answers
+pub fn explain(input: &str) -> anyhow::Result<String> {
+    Ok(report(&plans(input)?))
+}
+
+// Parses the machines and finds both parts' plans, each stage timed as a lap of `timer`
+fn timed_plans(input: &str, timer: &mut Timer) -> anyhow::Result<Vec<Plan>> {
+    let (lines, machines) = machines(input)
+        .map(|(line, machine)| machine.map(|m| (line.to_string(), m)))
+        .collect::<Result<(Vec<_>, Vec<_>), _>>()?;
+    timer.lap("parse");
+
+    let lights = machines
+        .iter()
+        .map(Machine::fewest_steps_to_target)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    timer.lap("part1");
+
+    let joltage = machines
+        .iter()
+        .map(Machine::fewest_steps_to_target_joltage)
+        .collect::<Vec<_>>();
+    timer.lap("part2");
+
+    Ok(lines
+        .into_iter()
+        .zip(machines)
+        .zip(lights.into_iter().zip(joltage))
+        .map(|((line, machine), (lights, joltage))| Plan {
+            line,
+            machine,
+            lights,
+            joltage,
+        })
+        .collect())
+}
+
+fn report(plans: &[Plan]) -> String {
+    let mut report = String::new();
+    for (i, plan) in plans.iter().enumerate() {
+        report += &format!("Machine {}: {}\n", i + 1, plan.line);
+
+        let checked = plan.lights.as_ref().map(|p| plan.machine.lights_match(p));
+        report += &plan_line("Lights", plan.lights.as_ref(), checked);
+
+        let checked = plan.joltage.as_ref().map(|p| plan.machine.joltage_matches(p));
+        report += &plan_line("Joltage", plan.joltage.as_ref(), checked);
+    }
+
+    report
+}
+
+// Every machine line with what it parses to. Blank lines are skipped.
+fn machines(input: &str) -> impl Iterator<Item = (&str, Result<Machine, ParseError>)> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (line, Machine::from_line(i + 1, line)))
+}
+
+// The numbers in a `(0,2)` or `{3,5}` token with the column each starts at, given the column the
+// token starts at
+fn numbers(token: &str, column: usize) -> impl Iterator<Item = (usize, &str)> {
+    token[1..token.len() - 1]
+        .split(',')
+        .scan(column + 1, |column, n| {
+            let start = *column;
+            *column += n.len() + 1;
+            Some((start, n))
+        })
+}
+
+fn plan_line(name: &str, presses: Option<&Presses>, checked: Option<bool>) -> String {
+    match (presses, checked) {
+        (Some(p), Some(true)) => format!("  {name}: {p} (verified)\n"),
+        (Some(p), _) => format!("  {name}: {p} (DOES NOT MATCH)\n"),
+        (None, _) => format!("  {name}: no solution\n"),
+    }
+}
+
+/// How often each button of a machine is pressed, indexed like the buttons on its input line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Presses(Vec<usize>);
+
+impl Presses {
+    pub fn new(counts: Vec<usize>) -> Self {
+        Self(counts)
+    }
+
+    /// How often each button is pressed, by its index on the input line
+    pub fn counts(&self) -> &[usize] {
+        &self.0
+    }
+
+    pub fn total(&self) -> usize {
+        self.0.iter().sum()
+    }
+}
+
+// e.g. `3 presses: button 1 x2, button 4 x1`
+impl Display for Presses {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} presses", self.total())?;
+        let pressed = self
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| **n > 0)
+            .map(|(i, n)| format!("button {i} x{n}"))
+            .collect::<Vec<_>>();
+        if !pressed.is_empty() {
+            write!(f, ": {}", pressed.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The lights, buttons and joltage counters from one input line
+pub struct Machine {
+    // Lights that have to end up on
+    target: BitVec,
+    // Lights each button toggles, in input order
+    transitions: Vec<BitVec>,
+    target_joltage: Vec<u16>,
+    // Counters each button increases, in input order
+    joltage_transitions: Vec<Vec<usize>>,
+}
+
+impl Machine {
+    fn from_line(line: usize, s: &str) -> Result<Self, ParseError> {
+        let error = |column, kind| ParseError { line, column, kind };
+
+        // Every space separated token with the column it starts at
+        let tokens = s
+            .split(' ')
+            .scan(1, |column, token| {
+                let start = *column;
+                *column += token.len() + 1;
+                Some((start, token))
+            })
+            .collect::<Vec<_>>();
+
+        let (_, lights) = tokens[0];
+        if !LIGHTS_RE.is_match(lights) {
+            return Err(error(1, ParseErrorKind::BadLights));
+        }
+        let target = Self::target_from_str(lights);
+        let light_count = lights.len() - 2;
+
+        let (joltage_column, joltage) = match tokens[1..].last() {
+            Some(&(column, token)) if JOLTAGE_RE.is_match(token) => (column, token),
+            Some(&(column, _)) => return Err(error(column, ParseErrorKind::BadJoltage)),
+            None => return Err(error(s.len() + 1, ParseErrorKind::BadJoltage)),
+        };
+        let target_joltage = numbers(joltage, joltage_column)
+            .map(|(column, n)| {
+                n.parse::<u16>()
+                    .map_err(|_| error(column, ParseErrorKind::BadJoltage))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if target_joltage.len() != light_count {
+            let kind = ParseErrorKind::JoltageLength {
+                lights: light_count,
+                found: target_joltage.len(),
+            };
+            return Err(error(joltage_column, kind));
+        }
+
+        let buttons = &tokens[1..tokens.len() - 1];
+        if buttons.is_empty() {
+            return Err(error(joltage_column, ParseErrorKind::NoButtons));
+        }
+
+        let mut transitions = Vec::new();
+        let mut joltage_transitions = Vec::new();
+        for &(column, button) in buttons {
+            if !BUTTON_RE.is_match(button) {
+                return Err(error(column, ParseErrorKind::BadButton));
+            }
+
+            let indices = numbers(button, column)
+                .map(|(column, n)| match n.parse::<usize>() {
+                    Ok(index) if index < light_count => Ok(index),
+                    Ok(index) => {
+                        let kind = ParseErrorKind::ButtonOutOfRange {
+                            index,
+                            lights: light_count,
+                        };
+                        Err(error(column, kind))
+                    }
+                    Err(_) => Err(error(column, ParseErrorKind::BadButton)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            transitions.push(BitVec::from_indices(indices.iter().copied()));
+            joltage_transitions.push(indices);
+        }
+
+        Ok(Self {
+            target,
+            transitions,
+            target_joltage,
+            joltage_transitions,
+        })
+    }
+
+    fn target_from_str(s: &str) -> BitVec {
+        BitVec::from_indices(
+            s.chars()
+                .skip(1)
+                .enumerate()
+                .filter(|(_, c)| *c == '#')
+                .map(|(i, _)| i),
+        )
+    }
+
+    // None if there is no solution, and an error if there are too many to search
+    fn fewest_steps_to_target(&self) -> anyhow::Result<Option<Presses>> {
+        let Some(pressed) = lights::fewest_presses(&self.transitions, &self.target)? else {
+            return Ok(None);
+        };
+        let presses = (0..self.transitions.len())
+            .map(|i| pressed.get(i) as usize)
+            .collect();
+
+        Ok(Some(Presses(presses)))
+    }
+
+    #[cfg(not(feature = "z3"))]
+    fn fewest_steps_to_target_joltage(&self) -> Option<Presses> {
+        self.fewest_steps_to_target_joltage_exact()
+    }
+
+    #[cfg(feature = "z3")]
+    fn fewest_steps_to_target_joltage(&self) -> Option<Presses> {
+        self.fewest_steps_to_target_joltage_z3()
+    }
+
+    #[cfg_attr(feature = "z3", allow(dead_code))]
+    fn fewest_steps_to_target_joltage_exact(&self) -> Option<Presses> {
+        joltage::fewest_presses(&self.joltage_transitions, &self.target_joltage).map(Presses)
+    }
+
+    #[cfg(feature = "z3")]
+    fn fewest_steps_to_target_joltage_z3(&self) -> Option<Presses> {
+        if self.target_joltage.iter().all(|n| *n == 0) {
+            return Some(Presses(vec![0; self.joltage_transitions.len()]));
+        }
+
+        let config = z3::Config::new();
+        let context = z3::Context::new(&config);
+        let opt = z3::Optimize::new(&context);
+
+        let buttons: Vec<_> = (0..self.joltage_transitions.len())
+            .map(|i| z3::ast::Int::new_const(&context, format!("b{}", i)))
+            .collect();
+
+        for b in &buttons {
+            opt.assert(&b.ge(&z3::ast::Int::from_i64(&context, 0)));
+        }
+
+        for (idx, &target) in self.target_joltage.iter().enumerate() {
+            let mut sum = z3::ast::Int::from_i64(&context, 0);
+            for (trans, button) in self.joltage_transitions.iter().zip(&buttons) {
+                let count = trans.iter().filter(|&&i| i == idx).count() as i64;
+                if count > 0 {
+                    sum = sum + button * count;
+                }
+            }
+            opt.assert(&sum._eq(&z3::ast::Int::from_i64(&context, target as i64)));
+        }
+
+        let total = buttons
+            .iter()
+            .fold(z3::ast::Int::from_i64(&context, 0), |acc, b| acc + b);
+        opt.minimize(&total);
+
+        if opt.check(&[]) == z3::SatResult::Sat {
+            let model = opt.get_model()?;
+            buttons
+                .iter()
+                .map(|b| model.eval(b, true)?.as_i64().map(|v| v as usize))
+                .collect::<Option<Vec<_>>>()
+                .map(Presses)
+        } else {
+            None
+        }
+    }
+
+    /// Whether pressing the buttons `presses` times turns on exactly the target lights, toggling
+    /// the lights of every button pressed an odd number of times
+    pub fn lights_match(&self, presses: &Presses) -> bool {
+        let mut lights = BitVec::new();
+        for (button, &n) in self.transitions.iter().zip(&presses.0) {
+            if n % 2 == 1 {
+                lights.toggle(button);
+            }
+        }
+
+        presses.0.len() == self.transitions.len() && lights == self.target
+    }
+
+    /// Whether pressing the buttons `presses` times brings every counter to its target, adding up
+    /// the counter increases of every press
+    pub fn joltage_matches(&self, presses: &Presses) -> bool {
+        let mut counters = vec![0; self.target_joltage.len()];
+        for (button, &n) in self.joltage_transitions.iter().zip(&presses.0) {
+            for &c in button {
+                match counters.get_mut(c) {
+                    Some(counter) => *counter += n,
+                    None => return false,
+                }
+            }
+        }
+
+        presses.0.len() == self.joltage_transitions.len()
+            && counters
+                .iter()
+                .zip(&self.target_joltage)
+                .all(|(&c, &t)| c == t as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(line: &str) -> (usize, ParseErrorKind) {
+        let error = Machine::from_line(1, line).err().unwrap();
+        (error.column, error.kind)
+    }
+
+    #[test]
+    fn test_target_from_str() {
+        assert_eq!(Machine::target_from_str("[####]"), BitVec::from(0b1111));
+        assert_eq!(Machine::target_from_str("[#..#]"), BitVec::from(0b1001));
+        assert_eq!(Machine::target_from_str("[##..]"), BitVec::from(0b0011));
+    }
+
+    #[test]
+    fn test_from_line() {
+        let machine = Machine::from_line(1, "[...#.] (0,2,3,4) (2,3) {7,5,12,7,2}").unwrap();
+        assert_eq!(machine.target, BitVec::from(0b01000));
+        assert_eq!(
+            machine.transitions,
+            [BitVec::from(0b11101), BitVec::from(0b01100)]
+        );
+        assert_eq!(machine.target_joltage, [7, 5, 12, 7, 2]);
+        assert_eq!(machine.joltage_transitions, [vec![0, 2, 3, 4], vec![2, 3]]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_error(""), (1, ParseErrorKind::BadLights));
+        assert_eq!(
+            parse_error("[.x] (0) {1,2}"),
+            (1, ParseErrorKind::BadLights)
+        );
+        assert_eq!(parse_error("[.#]"), (5, ParseErrorKind::BadJoltage));
+        assert_eq!(
+            parse_error("[.#] (0) {1,2"),
+            (10, ParseErrorKind::BadJoltage)
+        );
+        assert_eq!(parse_error("[.#] {1,2}"), (6, ParseErrorKind::NoButtons));
+        assert_eq!(
+            parse_error("[.#] (0) (0,) {1,2}"),
+            (10, ParseErrorKind::BadButton)
+        );
+        assert_eq!(
+            parse_error("[.#] (0) (0,12) {1,2}"),
+            (
+                13,
+                ParseErrorKind::ButtonOutOfRange {
+                    index: 12,
+                    lights: 2
+                }
+            )
+        );
+        assert_eq!(
+            parse_error("[.#] (0) {1,2,3}"),
+            (
+                10,
+                ParseErrorKind::JoltageLength {
+                    lights: 2,
+                    found: 3
+                }
+            )
+        );
+
+        let error = machines("[.#] (0) {1,2}\n\n[.#] (0) {1}")
+            .find_map(|(_, m)| m.err())
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 10: expected 2 joltage requirements, found 1"
+        );
+    }
+
+    #[test]
+    fn test_plans_are_verified() {
+        let input = aoc_common::read_test_input("day10.txt").unwrap();
+        let plans = plans(&input).unwrap();
+        assert_eq!(plans.len(), 3);
+        for plan in &plans {
+            assert!(plan.machine.lights_match(plan.lights.as_ref().unwrap()));
+            assert!(plan.machine.joltage_matches(plan.joltage.as_ref().unwrap()));
+        }
+        assert_eq!(plans[0].lights.as_ref().unwrap().counts(), [0, 1, 0, 1, 0, 0]);
+
+        let answers = solve_explained(&input).unwrap();
+        assert_eq!((answers.part1.as_deref(), answers.part2.as_deref()), (Some("7"), Some("33")));
+        assert_eq!(answers.report, Some(explain(&input).unwrap()));
+
+        let machine =
+            Machine::from_line(1, "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}").unwrap();
+        assert!(!machine.lights_match(&Presses(vec![1, 0, 0, 0, 0, 0])));
+        assert!(!machine.joltage_matches(&Presses(vec![0, 1, 0, 1, 0, 2])));
+        assert_eq!(
+            Presses(vec![0, 2, 0, 1]).to_string(),
+            "3 presses: button 1 x2, button 3 x1"
+        );
+    }
+
+    #[cfg(feature = "z3")]
+    #[test]
+    fn test_exact_solver_matches_z3() {
+        let input = aoc_common::read_test_input("day10.txt").unwrap();
+        for line in input.lines() {
+            let machine = Machine::from_line(1, line).unwrap();
+            // Both find a fewest-press plan, not necessarily the same one
+            assert_eq!(
+                machine
+                    .fewest_steps_to_target_joltage_exact()
+                    .map(|p| p.total()),
+                machine
+                    .fewest_steps_to_target_joltage_z3()
+                    .map(|p| p.total())
+            );
+        }
+    }
+}
@@ -0,0 +1,416 @@
+// Solver for the light puzzle. Pressing a button toggles its lights, so pressing it twice does
+// nothing and the order doesn't matter: each button is either pressed once or not at all, and
+// the question is which subset of buttons XORs to the target. That's a linear system over GF(2),
+// solved by Gaussian elimination. Every solution is then the particular solution plus some
+// combination of the null space basis. A small null space is walked in Gray code order to find
+// the solution that presses the fewest buttons; a bigger one is searched by branch and bound,
+// deciding the free buttons one at a time and dropping any branch that can't beat the best so far.
+// Finding the fewest presses is hard in general, so the search gives up after a set number of
+// steps rather than run for hours.
+
+use anyhow::bail;
+use std::fmt::{Debug, Formatter};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+// The most free buttons whose every combination is tried
+const GRAY_CODE_LIMIT: usize = 16;
+// How many branches the search may look at beyond that
+const SEARCH_STEPS: usize = 1 << 24;
+
+/// A set of bit indices of any width, packed into 64-bit words
+#[derive(Clone, Default)]
+pub(crate) struct BitVec {
+    words: Vec<u64>,
+}
+
+impl BitVec {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn from_indices(indices: impl IntoIterator<Item = usize>) -> Self {
+        let mut bits = Self::new();
+        for i in indices {
+            bits.set(i);
+        }
+        bits
+    }
+
+    pub(crate) fn set(&mut self, i: usize) {
+        let word = i / WORD_BITS;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (i % WORD_BITS);
+    }
+
+    pub(crate) fn get(&self, i: usize) -> bool {
+        self.words
+            .get(i / WORD_BITS)
+            .is_some_and(|w| w & (1 << (i % WORD_BITS)) != 0)
+    }
+
+    pub(crate) fn toggle(&mut self, other: &BitVec) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (w, o) in self.words.iter_mut().zip(&other.words) {
+            *w ^= o;
+        }
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &w)| {
+            (0..WORD_BITS)
+                .filter(move |b| w & (1 << b) != 0)
+                .map(move |b| i * WORD_BITS + b)
+        })
+    }
+}
+
+impl From<u64> for BitVec {
+    fn from(bits: u64) -> Self {
+        Self { words: vec![bits] }
+    }
+}
+
+// Trailing zero words don't change the set, so they don't count for equality
+impl PartialEq for BitVec {
+    fn eq(&self, other: &Self) -> bool {
+        let (short, long) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        long[..short.len()] == short[..] && long[short.len()..].iter().all(|&w| w == 0)
+    }
+}
+
+impl Eq for BitVec {}
+
+impl Debug for BitVec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Finds the smallest set of buttons whose lights XOR to `target`, as a set of button indices.
+/// Returns None if no set of buttons does, and fails if there are too many ways to press them to
+/// tell which is smallest.
+pub(crate) fn fewest_presses(
+    buttons: &[BitVec],
+    target: &BitVec,
+) -> anyhow::Result<Option<BitVec>> {
+    fewest_presses_within(buttons, target, GRAY_CODE_LIMIT)
+}
+
+// With up to `gray_code_limit` free buttons every combination of them is tried
+fn fewest_presses_within(
+    buttons: &[BitVec],
+    target: &BitVec,
+    gray_code_limit: usize,
+) -> anyhow::Result<Option<BitVec>> {
+    let lights = buttons
+        .iter()
+        .flat_map(|b| b.iter())
+        .chain(target.iter())
+        .max()
+        .map_or(0, |max| max + 1);
+
+    // One row per light: which buttons toggle it, and whether it has to end up on. The right hand
+    // side lives in the bit just past the last button.
+    let rhs = buttons.len();
+    let mut rows = (0..lights)
+        .map(|light| {
+            let mut row = BitVec::from_indices(
+                buttons
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| b.get(light))
+                    .map(|(i, _)| i),
+            );
+            if target.get(light) {
+                row.set(rhs);
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+
+    let mut pivots = Vec::new();
+    for col in 0..buttons.len() {
+        let r = pivots.len();
+        let Some(p) = (r..rows.len()).find(|&p| rows[p].get(col)) else {
+            continue;
+        };
+        rows.swap(r, p);
+
+        let pivot_row = rows[r].clone();
+        for (k, row) in rows.iter_mut().enumerate() {
+            if k != r && row.get(col) {
+                row.toggle(&pivot_row);
+            }
+        }
+        pivots.push(col);
+    }
+
+    // A row with no buttons left that still needs its light on can't be satisfied
+    if rows[pivots.len()..].iter().any(|row| row.get(rhs)) {
+        return Ok(None);
+    }
+
+    // Free buttons unpressed, each pivot button pressed if its row says so
+    let mut current = BitVec::from_indices(
+        pivots
+            .iter()
+            .zip(&rows)
+            .filter(|(_, row)| row.get(rhs))
+            .map(|(&col, _)| col),
+    );
+
+    let free = (0..buttons.len())
+        .filter(|col| !pivots.contains(col))
+        .collect::<Vec<_>>();
+    if free.len() > gray_code_limit {
+        let mut search = Search::new(&free, &pivots, &rows, rhs);
+        search.run(0, search.settled());
+        if search.steps > SEARCH_STEPS {
+            bail!(
+                "Too many combinations of {} free buttons to find the fewest presses",
+                free.len()
+            );
+        }
+        return Ok(Some(search.best));
+    }
+
+    // Pressing a free button, plus every pivot button whose row it appears in, changes nothing
+    let basis = free
+        .iter()
+        .map(|&free| {
+            let mut v = BitVec::from_indices(
+                pivots
+                    .iter()
+                    .zip(&rows)
+                    .filter(|(_, row)| row.get(free))
+                    .map(|(&col, _)| col),
+            );
+            v.set(free);
+            v
+        })
+        .collect::<Vec<_>>();
+
+    // Gray code order flips a single basis vector per step
+    let mut best = current.clone();
+    for step in 1..1usize << basis.len() {
+        current.toggle(&basis[step.trailing_zeros() as usize]);
+        if current.count() < best.count() {
+            best = current.clone();
+        }
+    }
+
+    Ok(Some(best))
+}
+
+// Branch and bound over the free buttons. Each pivot button is pressed when its row's right hand
+// side, flipped by every pressed free button in the row, ends up set. Once the last free button
+// in a row is decided, so is its pivot button, and the presses decided so far bound the total.
+struct Search {
+    // The rows each free button appears in, as a set of row indices, in the order they're decided
+    columns: Vec<BitVec>,
+    // The rows whose last free button is the one at the same index
+    closing: Vec<BitVec>,
+    free: Vec<usize>,
+    pivots: Vec<usize>,
+    // Which pivot buttons are pressed with the free buttons decided so far
+    parity: BitVec,
+    pressed: Vec<bool>,
+    best: BitVec,
+    best_count: usize,
+    steps: usize,
+}
+
+impl Search {
+    fn new(free: &[usize], pivots: &[usize], rows: &[BitVec], rhs: usize) -> Self {
+        let rows = &rows[..pivots.len()];
+        // Buttons in the most rows first, so rows close early and their pivots count sooner
+        let mut free = free.to_vec();
+        free.sort_by_key(|&f| std::cmp::Reverse(rows.iter().filter(|r| r.get(f)).count()));
+
+        let columns = free
+            .iter()
+            .map(|&f| BitVec::from_indices((0..rows.len()).filter(|&r| rows[r].get(f))))
+            .collect::<Vec<_>>();
+        let mut closing = vec![BitVec::new(); free.len()];
+        for r in 0..rows.len() {
+            if let Some(last) = (0..free.len()).rev().find(|&i| columns[i].get(r)) {
+                closing[last].set(r);
+            }
+        }
+
+        // Pressing no free buttons at all is the solution to beat
+        let parity = BitVec::from_indices((0..rows.len()).filter(|&r| rows[r].get(rhs)));
+        let best = BitVec::from_indices(parity.iter().map(|r| pivots[r]));
+
+        Self {
+            columns,
+            closing,
+            pressed: vec![false; free.len()],
+            free,
+            pivots: pivots.to_vec(),
+            parity,
+            best_count: best.count(),
+            best,
+            steps: 0,
+        }
+    }
+
+    // The pivot buttons of rows without free buttons are pressed whatever happens
+    fn settled(&self) -> usize {
+        let decided = self.closing.iter().fold(BitVec::new(), |mut all, rows| {
+            all.toggle(rows);
+            all
+        });
+        self.parity.iter().filter(|&r| !decided.get(r)).count()
+    }
+
+    fn run(&mut self, i: usize, count: usize) {
+        self.steps += 1;
+        if count >= self.best_count || self.steps > SEARCH_STEPS {
+            return;
+        }
+        if i == self.free.len() {
+            self.best = BitVec::from_indices(
+                self.parity
+                    .iter()
+                    .map(|r| self.pivots[r])
+                    .chain((0..i).filter(|&j| self.pressed[j]).map(|j| self.free[j])),
+            );
+            self.best_count = count;
+            return;
+        }
+
+        for press in [false, true] {
+            if press {
+                self.parity.toggle(&self.columns[i]);
+                self.pressed[i] = true;
+            }
+            let settled = self.closing[i]
+                .iter()
+                .filter(|&r| self.parity.get(r))
+                .count();
+            self.run(i + 1, count + usize::from(press) + settled);
+            if press {
+                self.parity.toggle(&self.columns[i]);
+                self.pressed[i] = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buttons(s: &[&[usize]]) -> Vec<BitVec> {
+        s.iter()
+            .map(|b| BitVec::from_indices(b.iter().copied()))
+            .collect()
+    }
+
+    #[test]
+    fn test_bit_vec() {
+        let mut bits = BitVec::from_indices([0, 3, 70]);
+        assert!(bits.get(70) && !bits.get(69));
+        assert_eq!(bits.count(), 3);
+        assert_eq!(bits.iter().collect::<Vec<_>>(), [0, 3, 70]);
+
+        bits.toggle(&BitVec::from_indices([70]));
+        assert_eq!(bits, BitVec::from(0b1001));
+    }
+
+    #[test]
+    fn test_sample_machines() {
+        let b = buttons(&[&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]]);
+        let presses = fewest_presses(&b, &BitVec::from(0b0110)).unwrap().unwrap();
+        assert_eq!(presses.count(), 2);
+
+        let b = buttons(&[&[0, 2, 3, 4], &[2, 3], &[0, 4], &[0, 1, 2], &[1, 2, 3, 4]]);
+        assert_eq!(
+            fewest_presses(&b, &BitVec::from(0b01000))
+                .unwrap()
+                .unwrap()
+                .count(),
+            3
+        );
+
+        assert_eq!(
+            fewest_presses(&b, &BitVec::new()).unwrap(),
+            Some(BitVec::new())
+        );
+        assert_eq!(
+            fewest_presses(&buttons(&[&[0, 1]]), &BitVec::from(0b01)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_wide_machine() {
+        // 100 lights, each button toggles a neighbouring pair, so lighting the two ends takes
+        // the whole chain
+        let chain = (0..99).map(|i| vec![i, i + 1]).collect::<Vec<_>>();
+        let b = chain
+            .iter()
+            .map(|b| BitVec::from_indices(b.iter().copied()))
+            .collect::<Vec<_>>();
+        let presses = fewest_presses(&b, &BitVec::from_indices([0, 99]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(presses.count(), 99);
+    }
+
+    #[test]
+    fn test_big_null_space() {
+        // 70 buttons on one light leave 69 free buttons, far too many combinations to try
+        let b = vec![BitVec::from_indices([0]); 70];
+        let presses = fewest_presses(&b, &BitVec::from_indices([0]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(presses.count(), 1);
+        assert_eq!(
+            fewest_presses(&b, &BitVec::new()).unwrap(),
+            Some(BitVec::new())
+        );
+    }
+
+    #[test]
+    fn test_search_against_gray_code() {
+        let mut seed = 7u64;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        for _ in 0..50 {
+            let b = (0..24)
+                .map(|_| BitVec::from(random() & 0xff))
+                .collect::<Vec<_>>();
+            let target = BitVec::from(random() & 0xff);
+            let searched = fewest_presses_within(&b, &target, 0).unwrap();
+            let tried = fewest_presses_within(&b, &target, usize::MAX).unwrap();
+            assert_eq!(
+                searched.as_ref().map(|p| p.count()),
+                tried.map(|p| p.count())
+            );
+            if let Some(presses) = searched {
+                let mut lights = BitVec::new();
+                presses.iter().for_each(|i| lights.toggle(&b[i]));
+                assert_eq!(lights, target);
+            }
+        }
+    }
+}
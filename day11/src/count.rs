@@ -0,0 +1,78 @@
+use num_bigint::BigUint;
+use std::fmt::{Display, Formatter};
+
+/// A number paths can be counted in. Counts only ever get added up, and adding fails when the sum
+/// doesn't fit.
+pub trait PathCount: Clone {
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+}
+
+impl PathCount for u64 {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        u64::checked_add(*self, *other)
+    }
+}
+
+impl PathCount for u128 {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        u128::checked_add(*self, *other)
+    }
+}
+
+impl PathCount for BigUint {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+}
+
+/// A count modulo `modulus`, which never overflows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Modulo {
+    pub value: u64,
+    pub modulus: u64,
+}
+
+impl Modulo {
+    pub fn new(value: u64, modulus: u64) -> Self {
+        Self {
+            value: value % modulus,
+            modulus,
+        }
+    }
+}
+
+impl PathCount for Modulo {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        // Both are below the modulus, so their sum fits a u128
+        let sum = (self.value as u128 + other.value as u128) % self.modulus as u128;
+        Some(Self::new(sum as u64, self.modulus))
+    }
+}
+
+impl Display for Modulo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_add() {
+        assert_eq!(PathCount::checked_add(&u64::MAX, &1), None);
+        assert_eq!(
+            PathCount::checked_add(&(u64::MAX as u128), &1),
+            Some(1 << 64)
+        );
+        assert_eq!(
+            PathCount::checked_add(&BigUint::from(u128::MAX), &BigUint::from(1u8)),
+            Some(BigUint::from(u128::MAX) + 1u8)
+        );
+        assert_eq!(
+            Modulo::new(u64::MAX - 1, u64::MAX).checked_add(&Modulo::new(5, u64::MAX)),
+            Some(Modulo::new(4, u64::MAX))
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{Graph, PathQuery};
+use anyhow::bail;
+use num_bigint::BigUint;
+
+// Highlighted when the query doesn't name any waypoints of its own
+const DEFAULT_WAYPOINTS: [&str; 2] = ["dac", "fft"];
+
+impl Graph {
+    /// The graph in Graphviz DOT. With a query every node is labelled with its number of paths
+    /// from the query's start and to its end (ignoring the waypoints), and with `prune` only the
+    /// nodes on some path from start to end are kept. The query's `via` nodes, or dac and fft
+    /// without them, are highlighted.
+    pub fn to_dot(&self, query: Option<&PathQuery>, prune: bool) -> anyhow::Result<String> {
+        if prune && query.is_none() {
+            bail!("Pruning needs a start and an end, given with --from and --to");
+        }
+
+        let counts = match query {
+            Some(query) => {
+                let (start, end) = (self.node(&query.from)?, self.node(&query.to)?);
+                Some((self.paths_from(start), self.paths_to(end)))
+            }
+            None => None,
+        };
+        let keep = |node: usize| match &counts {
+            Some((from, to)) if prune => from[node] != BigUint::ZERO && to[node] != BigUint::ZERO,
+            _ => true,
+        };
+
+        let waypoints = match query {
+            Some(query) if !query.via.is_empty() => query.via.clone(),
+            _ => DEFAULT_WAYPOINTS.map(String::from).to_vec(),
+        };
+
+        let mut dot = String::from("digraph reactor {\n");
+        for (node, name) in self.names.iter().enumerate().filter(|(n, _)| keep(*n)) {
+            let mut attributes = Vec::new();
+            if let (Some(query), Some((from, to))) = (query, &counts) {
+                attributes.push(format!(
+                    "label=\"{name}\\nfrom {}: {}\\nto {}: {}\"",
+                    query.from, from[node], query.to, to[node]
+                ));
+                if *name == query.from || *name == query.to {
+                    attributes.push("shape=doublecircle".to_string());
+                }
+            }
+            if waypoints.contains(name) {
+                attributes.push("style=filled, fillcolor=gold".to_string());
+            }
+
+            match attributes.is_empty() {
+                true => dot += &format!("    {};\n", quote(name)),
+                false => dot += &format!("    {} [{}];\n", quote(name), attributes.join(", ")),
+            }
+        }
+        for (node, edges) in self.edges.iter().enumerate().filter(|(n, _)| keep(*n)) {
+            for &to in edges.iter().filter(|&&to| keep(to)) {
+                dot += &format!(
+                    "    {} -> {};\n",
+                    quote(&self.names[node]),
+                    quote(&self.names[to])
+                );
+            }
+        }
+        dot += "}\n";
+
+        Ok(dot)
+    }
+
+    // Number of paths from `start` to every node, in topological order
+    fn paths_from(&self, start: usize) -> Vec<BigUint> {
+        let mut paths = vec![BigUint::ZERO; self.names.len()];
+        paths[start] = BigUint::from(1u8);
+        for &node in &self.order {
+            for &to in &self.edges[node] {
+                let count = paths[node].clone();
+                paths[to] += count;
+            }
+        }
+
+        paths
+    }
+
+    // Number of paths from every node to `end`, in reverse topological order
+    fn paths_to(&self, end: usize) -> Vec<BigUint> {
+        let mut paths = vec![BigUint::ZERO; self.names.len()];
+        paths[end] = BigUint::from(1u8);
+        for &node in self.order.iter().rev() {
+            if node == end {
+                continue;
+            }
+            for &to in &self.edges[node] {
+                let count = paths[to].clone();
+                paths[node] += count;
+            }
+        }
+
+        paths
+    }
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_dot() {
+        let graph = "svr: dac zzz\ndac: out\nzzz: yyy".parse::<Graph>().unwrap();
+        assert_eq!(
+            graph.to_dot(None, false).unwrap(),
+            "digraph reactor {\n    \"svr\";\n    \"dac\" [style=filled, fillcolor=gold];\n    \
+             \"zzz\";\n    \"out\";\n    \"yyy\";\n    \"svr\" -> \"dac\";\n    \
+             \"svr\" -> \"zzz\";\n    \"dac\" -> \"out\";\n    \"zzz\" -> \"yyy\";\n}\n"
+        );
+
+        let query = PathQuery::new("svr", "out");
+        let dot = graph.to_dot(Some(&query), true).unwrap();
+        assert!(dot.contains("\"dac\" [label=\"dac\\nfrom svr: 1\\nto out: 1\", style=filled"));
+        assert!(
+            dot.contains("\"svr\" [label=\"svr\\nfrom svr: 1\\nto out: 1\", shape=doublecircle]")
+        );
+        assert!(!dot.contains("zzz"));
+
+        assert!(
+            graph
+                .to_dot(Some(&PathQuery::new("you", "out")), true)
+                .is_err()
+        );
+    }
+}
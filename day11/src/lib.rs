@@ -0,0 +1,567 @@
+mod count;
+mod dot;
+mod paths;
+
+pub use count::{Modulo, PathCount};
+pub use paths::{PathSampler, Paths};
+
+use anyhow::{Context, bail};
+use aoc_common::{Answers, Cli, Timer};
+use num_bigint::BigUint;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+// Every node keeps one path count per subset of the waypoints, so this keeps that table sane
+const MAX_VIA: usize = 12;
+
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let mut timer = Timer::start();
+    let graph = input.parse::<Graph>()?;
+    timer.lap("parse");
+
+    // The samples for the two parts are separate graphs, so only answer the parts whose start
+    // node is actually there
+    let part1 = match graph.ids.contains_key("you") {
+        true => Some(graph.count_paths("you", "out")?),
+        false => None,
+    };
+    timer.lap("part1");
+
+    let part2 = match graph.ids.contains_key("svr") {
+        true => Some(graph.part2("svr", "out")?),
+        false => None,
+    };
+    timer.lap("part2");
+
+    Ok(Answers {
+        part1: part1.map(|n| n.to_string()),
+        part2: part2.map(|n| n.to_string()),
+        timings: timer.finish(),
+        report: None,
+    })
+}
+
+/// Which paths to count: from `from` to `to`, through at least `at_least` of the `via` nodes (all
+/// of them if not given) and through none of the `avoid` nodes. With `modulo` only the count
+/// modulo that is wanted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathQuery {
+    pub from: String,
+    pub to: String,
+    pub via: Vec<String>,
+    pub avoid: Vec<String>,
+    pub at_least: Option<usize>,
+    pub modulo: Option<u64>,
+}
+
+impl PathQuery {
+    pub fn new(from: &str, to: &str) -> Self {
+        Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// The query given by `--from A --to B [--via X,Y] [--avoid Z] [--at-least K] [--modulo M]`,
+    /// or None if none of those are there and the puzzle should be solved as usual
+    pub fn from_cli(cli: &Cli) -> anyhow::Result<Option<Self>> {
+        let options = [
+            "--from",
+            "--to",
+            "--via",
+            "--avoid",
+            "--at-least",
+            "--modulo",
+        ];
+        if !options.iter().any(|o| cli.flag(o)) {
+            return Ok(None);
+        }
+
+        let (Some(from), Some(to)) = (cli.value("--from")?, cli.value("--to")?) else {
+            bail!("A path query needs both --from and --to");
+        };
+        let list = |name| -> anyhow::Result<Vec<String>> {
+            Ok(cli
+                .value(name)?
+                .map(|v| v.split(',').map(str::to_string).collect())
+                .unwrap_or_default())
+        };
+        let at_least = match cli.value("--at-least")? {
+            Some(k) => Some(
+                k.parse::<usize>()
+                    .with_context(|| format!("Invalid waypoint count {k}"))?,
+            ),
+            None => None,
+        };
+        let modulo = match cli.value("--modulo")? {
+            Some(m) => match m.parse::<u64>() {
+                Ok(m) if m > 0 => Some(m),
+                _ => bail!("Invalid modulus {m}, expected a positive number"),
+            },
+            None => None,
+        };
+
+        Ok(Some(Self {
+            via: list("--via")?,
+            avoid: list("--avoid")?,
+            at_least,
+            modulo,
+            ..Self::new(from, to)
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct Graph {
+    // Node id of every name, and the name of every id
+    ids: HashMap<String, usize>,
+    names: Vec<String>,
+    // Outgoing edges by node id
+    edges: Vec<Vec<usize>>,
+    // Every node comes before all the nodes it has edges to
+    order: Vec<usize>,
+}
+
+impl FromStr for Graph {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut graph = Self {
+            ids: HashMap::new(),
+            names: Vec::new(),
+            edges: Vec::new(),
+            order: Vec::new(),
+        };
+
+        // Nodes that only ever appear as an edge's target get no line of their own, and are sinks
+        for (i, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Some((vertex, edges)) = line.split_once(':') else {
+                bail!("Line {} has no ':' after the node name: {line}", i + 1);
+            };
+            if vertex.trim().is_empty() {
+                bail!("Line {} has no node name: {line}", i + 1);
+            }
+
+            let vertex = graph.id(vertex.trim());
+            for edge in edges.split_whitespace() {
+                let to = graph.id(edge);
+                if !graph.edges[vertex].contains(&to) {
+                    graph.edges[vertex].push(to);
+                }
+            }
+        }
+
+        graph.order = graph.topological_order()?;
+
+        Ok(graph)
+    }
+}
+
+impl Graph {
+    // Id of the node called `name`, adding it if it's new
+    fn id(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+
+        let id = self.names.len();
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        self.edges.push(Vec::new());
+        id
+    }
+
+    // Id of an existing node
+    fn node(&self, name: &str) -> anyhow::Result<usize> {
+        self.ids
+            .get(name)
+            .copied()
+            .with_context(|| format!("There is no node called {name}"))
+    }
+
+    // Kahn's algorithm: repeatedly take a node nothing left points to
+    fn topological_order(&self) -> anyhow::Result<Vec<usize>> {
+        let mut incoming = vec![0; self.names.len()];
+        for to in self.edges.iter().flatten() {
+            incoming[*to] += 1;
+        }
+
+        let mut ready = (0..self.names.len())
+            .filter(|&n| incoming[n] == 0)
+            .collect::<Vec<_>>();
+        let mut order = Vec::with_capacity(self.names.len());
+        while let Some(node) = ready.pop() {
+            order.push(node);
+            for &to in &self.edges[node] {
+                incoming[to] -= 1;
+                if incoming[to] == 0 {
+                    ready.push(to);
+                }
+            }
+        }
+
+        if order.len() < self.names.len() {
+            let cycle = self
+                .find_cycle(&incoming)
+                .iter()
+                .map(|&n| self.names[n].as_str())
+                .collect::<Vec<_>>();
+            bail!("Graph has a cycle: {}", cycle.join(" -> "));
+        }
+
+        Ok(order)
+    }
+
+    // Kahn's algorithm leaves exactly the nodes on or behind a cycle with incoming edges, each
+    // from another such node. Following those edges backwards must come round to a node again.
+    // Returns the cycle in edge order, with its first node repeated at the end.
+    fn find_cycle(&self, incoming: &[usize]) -> Vec<usize> {
+        let mut previous = vec![None; self.names.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            if incoming[from] > 0 {
+                for &to in edges {
+                    previous[to] = Some(from);
+                }
+            }
+        }
+
+        let Some(mut node) = (0..self.names.len()).find(|&n| incoming[n] > 0) else {
+            return Vec::new();
+        };
+        let mut seen = vec![false; self.names.len()];
+        while !seen[node] {
+            seen[node] = true;
+            node = previous[node].expect("nodes left by Kahn's algorithm have a predecessor");
+        }
+
+        let mut cycle = vec![node];
+        let mut current = previous[node].unwrap();
+        while current != node {
+            cycle.push(current);
+            current = previous[current].unwrap();
+        }
+        cycle.push(node);
+        cycle.reverse();
+
+        cycle
+    }
+
+    fn count_paths(&self, start: &str, end: &str) -> anyhow::Result<BigUint> {
+        self.count(&PathQuery::new(start, end))
+    }
+
+    // Paths that go through both dac and fft
+    fn part2(&self, start: &str, end: &str) -> anyhow::Result<BigUint> {
+        let query = PathQuery {
+            via: vec!["dac".to_string(), "fft".to_string()],
+            ..PathQuery::new(start, end)
+        };
+        self.count(&query)
+    }
+
+    /// Counts the paths exactly, in a u64 while it fits and in a big integer once it doesn't, or
+    /// modulo the query's modulus if it has one
+    pub fn count(&self, query: &PathQuery) -> anyhow::Result<BigUint> {
+        if let Some(modulus) = query.modulo {
+            let count = self.count_as(query, Modulo::new(0, modulus), Modulo::new(1, modulus))?;
+            return Ok(count.value.into());
+        }
+
+        match self.try_count(query, 0u64, 1u64)? {
+            Some(count) => Ok(count.into()),
+            None => self.count_as(query, BigUint::ZERO, BigUint::from(1u8)),
+        }
+    }
+
+    /// Counts the paths in any `PathCount` type, given its counts of none and of a single path.
+    /// Fails if the count doesn't fit.
+    pub fn count_as<T: PathCount>(&self, query: &PathQuery, zero: T, one: T) -> anyhow::Result<T> {
+        self.try_count(query, zero, one)?
+            .context("Too many paths to count in this type")
+    }
+
+    // None if the count doesn't fit in T
+    fn try_count<T: PathCount>(
+        &self,
+        query: &PathQuery,
+        zero: T,
+        one: T,
+    ) -> anyhow::Result<Option<T>> {
+        let Some(table) = self.path_table(query, zero.clone(), one)? else {
+            return Ok(None);
+        };
+
+        Ok(table.completions(table.start, 0, zero))
+    }
+
+    // How many paths from each node to the query's end avoid all of its `avoid` nodes and visit
+    // exactly each subset of its `via` nodes. Walks the nodes backwards in topological order so
+    // every node's successors are done before it, which makes it linear in the edges for a fixed
+    // number of waypoints. Fails if the start or end isn't in the graph at all, as opposed to
+    // there just being no paths between them, and is None if a count doesn't fit in T.
+    fn path_table<T: PathCount>(
+        &self,
+        query: &PathQuery,
+        zero: T,
+        one: T,
+    ) -> anyhow::Result<Option<PathTable<T>>> {
+        if query.via.len() > MAX_VIA {
+            bail!("At most {MAX_VIA} --via nodes are supported");
+        }
+        let (start, end) = (self.node(&query.from)?, self.node(&query.to)?);
+
+        let mut bits = vec![0; self.names.len()];
+        for (i, name) in query.via.iter().enumerate() {
+            if let Some(&id) = self.ids.get(name) {
+                bits[id] |= 1 << i;
+            }
+        }
+        let mut avoided = vec![false; self.names.len()];
+        for name in &query.avoid {
+            if let Some(&id) = self.ids.get(name) {
+                avoided[id] = true;
+            }
+        }
+
+        // Only nodes the start reaches count towards its paths, and leaving the rest out keeps
+        // their counts from overflowing when the start's own count fits
+        let mut reached = vec![false; self.names.len()];
+        reached[start] = true;
+        for &node in &self.order {
+            if reached[node] && !avoided[node] {
+                self.edges[node].iter().for_each(|&to| reached[to] = true);
+            }
+        }
+
+        let subsets = 1 << query.via.len();
+        let mut paths = vec![vec![zero; subsets]; self.names.len()];
+        if !avoided[end] {
+            paths[end][bits[end]] = one;
+        }
+        for &node in self.order.iter().rev() {
+            if node == end || avoided[node] || !reached[node] {
+                continue;
+            }
+            for &to in &self.edges[node] {
+                for seen in 0..subsets {
+                    let Some(sum) = paths[node][seen | bits[node]].checked_add(&paths[to][seen])
+                    else {
+                        return Ok(None);
+                    };
+                    paths[node][seen | bits[node]] = sum;
+                }
+            }
+        }
+
+        Ok(Some(PathTable {
+            start,
+            end,
+            bits,
+            at_least: query.at_least.unwrap_or(query.via.len()),
+            paths,
+        }))
+    }
+}
+
+// The path counts of a query, see `Graph::path_table`
+struct PathTable<T> {
+    start: usize,
+    end: usize,
+    // Waypoint bit of every node, 0 if it isn't one
+    bits: Vec<usize>,
+    at_least: usize,
+    // By node and then by the bitmask of the waypoints visited from it on
+    paths: Vec<Vec<T>>,
+}
+
+impl<T: PathCount> PathTable<T> {
+    // Paths from `node` to the end that, after the waypoints in `seen` were already visited
+    // before `node`, end up with enough of them. None if the sum doesn't fit in T.
+    fn completions(&self, node: usize, seen: usize, zero: T) -> Option<T> {
+        self.paths[node]
+            .iter()
+            .enumerate()
+            .filter(|(subset, _)| (seen | subset).count_ones() as usize >= self.at_least)
+            .try_fold(zero, |acc, (_, count)| acc.checked_add(count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1() {
+        let input = aoc_common::read_test_input("day11.txt").unwrap();
+        let graph = Graph::from_str(&input).unwrap();
+        assert_eq!(
+            graph.count_paths("you", "out").unwrap(),
+            BigUint::from(5u32)
+        );
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = aoc_common::read_test_input("day11part2.txt").unwrap();
+        let graph = Graph::from_str(&input).unwrap();
+        assert_eq!(graph.part2("svr", "out").unwrap(), BigUint::from(2u32));
+        assert_eq!(
+            graph.count_paths("svr", "out").unwrap(),
+            BigUint::from(8u32)
+        );
+    }
+
+    #[test]
+    fn test_queries() {
+        let input = aoc_common::read_test_input("day11part2.txt").unwrap();
+        let graph = input.parse::<Graph>().unwrap();
+        let query = |via: &[&str], avoid: &[&str], at_least| PathQuery {
+            via: via.iter().map(|s| s.to_string()).collect(),
+            avoid: avoid.iter().map(|s| s.to_string()).collect(),
+            at_least,
+            ..PathQuery::new("svr", "out")
+        };
+
+        assert_eq!(
+            graph.count(&query(&["dac", "fft"], &[], None)).unwrap(),
+            BigUint::from(2u32)
+        );
+        assert_eq!(
+            graph.count(&query(&["dac", "fft"], &[], Some(1))).unwrap(),
+            BigUint::from(6u32)
+        );
+        assert_eq!(
+            graph.count(&query(&["dac", "fft"], &[], Some(0))).unwrap(),
+            BigUint::from(8u32)
+        );
+        assert_eq!(
+            graph.count(&query(&[], &["dac", "fft"], None)).unwrap(),
+            BigUint::from(2u32)
+        );
+        assert_eq!(
+            graph.count(&query(&["hub"], &["aaa"], None)).unwrap(),
+            BigUint::from(2u32)
+        );
+        assert_eq!(
+            graph.count(&query(&[], &["out"], None)).unwrap(),
+            BigUint::from(0u32)
+        );
+    }
+
+    #[test]
+    fn test_invalid_graphs() {
+        let error = "aaa: bbb\nbbb: ccc out\nccc: ddd\nddd: bbb"
+            .parse::<Graph>()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Graph has a cycle: bbb -> ccc -> ddd -> bbb"
+        );
+        assert_eq!(
+            "aaa: aaa".parse::<Graph>().unwrap_err().to_string(),
+            "Graph has a cycle: aaa -> aaa"
+        );
+        assert!("aaa bbb".parse::<Graph>().is_err());
+    }
+
+    #[test]
+    fn test_missing_nodes() {
+        // zzz has no line of its own, so it's a sink rather than an error
+        let graph = "you: zzz out\n\nzzz:".parse::<Graph>().unwrap();
+        assert_eq!(
+            graph.count_paths("you", "out").unwrap(),
+            BigUint::from(1u32)
+        );
+        assert_eq!(
+            graph.count_paths("zzz", "out").unwrap(),
+            BigUint::from(0u32)
+        );
+        assert_eq!(
+            graph.count_paths("svr", "out").unwrap_err().to_string(),
+            "There is no node called svr"
+        );
+
+        let graph = "you: zzz out".parse::<Graph>().unwrap();
+        assert_eq!(
+            graph.count_paths("you", "zzz").unwrap(),
+            BigUint::from(1u32)
+        );
+    }
+
+    #[test]
+    fn test_query_from_cli() {
+        let cli = |args: &str| Cli::parse(args.split_whitespace().map(str::to_string)).unwrap();
+
+        assert_eq!(PathQuery::from_cli(&cli("--time")).unwrap(), None);
+        assert_eq!(
+            PathQuery::from_cli(&cli("--from svr --to out --via dac,fft --avoid xyz")).unwrap(),
+            Some(PathQuery {
+                via: vec!["dac".to_string(), "fft".to_string()],
+                avoid: vec!["xyz".to_string()],
+                ..PathQuery::new("svr", "out")
+            })
+        );
+        assert!(PathQuery::from_cli(&cli("--via dac")).is_err());
+        assert!(PathQuery::from_cli(&cli("--from a --to b --at-least x")).is_err());
+        assert!(PathQuery::from_cli(&cli("--from a --to b --modulo 0")).is_err());
+    }
+
+    #[test]
+    fn test_separate_graphs() {
+        // Same node names, different edges: nothing carries over from one graph to the other
+        let a = Graph::from_str("svr: dac\ndac: fft\nfft: out").unwrap();
+        let b = Graph::from_str("svr: dac fft\ndac: out\nfft: out").unwrap();
+        assert_eq!(a.part2("svr", "out").unwrap(), BigUint::from(1u32));
+        assert_eq!(b.part2("svr", "out").unwrap(), BigUint::from(0u32));
+        assert_eq!(b.count_paths("svr", "out").unwrap(), BigUint::from(2u32));
+    }
+
+    #[test]
+    fn test_long_chain() {
+        // Every node has two edges to the next, so 2^60 paths, far too many to walk one by one
+        let input = (0..60)
+            .map(|i| format!("n{i}: a{i} b{i}\na{i}: n{}\nb{i}: n{}\n", i + 1, i + 1))
+            .collect::<String>();
+        let graph = Graph::from_str(&input).unwrap();
+        assert_eq!(
+            graph.count_paths("n0", "n60").unwrap(),
+            BigUint::from(1u64 << 60)
+        );
+    }
+
+    #[test]
+    fn test_big_counts() {
+        // 2^200 paths overflow a u64 and a u128 but still come out exactly
+        let input = (0..200)
+            .map(|i| format!("n{i}: a{i} b{i}\na{i}: n{}\nb{i}: n{}\n", i + 1, i + 1))
+            .collect::<String>();
+        let graph = Graph::from_str(&input).unwrap();
+        let query = PathQuery::new("n0", "n200");
+
+        assert_eq!(graph.count(&query).unwrap(), BigUint::from(1u8) << 200);
+        assert!(graph.count_as(&query, 0u128, 1).is_err());
+        assert_eq!(
+            graph
+                .count_as(&PathQuery::new("n73", "n200"), 0u128, 1)
+                .unwrap(),
+            1 << 127
+        );
+        assert_eq!(
+            graph
+                .count_as(&PathQuery::new("n199", "n200"), 0u64, 1)
+                .unwrap(),
+            2
+        );
+
+        let query = PathQuery {
+            modulo: Some(1_000_000_007),
+            ..query
+        };
+        let expected = (BigUint::from(1u8) << 200) % 1_000_000_007u32;
+        assert_eq!(graph.count(&query).unwrap(), expected);
+    }
+}
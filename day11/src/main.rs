@@ -1,118 +1,54 @@
-use cached::SizedCache;
-use cached::proc_macro::cached;
-use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::BufRead;
-use std::path::{Path, PathBuf};
+use anyhow::{Context, bail};
+use aoc_common::Cli;
+use day11::{Graph, PathQuery};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 
 fn main() -> anyhow::Result<()> {
-    let args = std::env::args().collect::<Vec<_>>();
-    let dir = if args.contains(&String::from("--test_input")) {
-        PathBuf::from("test_input")
-    } else {
-        PathBuf::from("input")
+    // `--from A --to B ...` counts the paths of one query instead of solving the puzzle, and with
+    // `--paths` lists them or with `--sample N [--seed S]` draws N of them at random. `--dot
+    // [--prune]` prints the graph, annotated with the query's path counts if there is one.
+    let cli = Cli::from_env()?;
+    let query = PathQuery::from_cli(&cli)?;
+    if cli.flag("--dot") {
+        let graph = cli.read_input(11)?.parse::<Graph>()?;
+        print!("{}", graph.to_dot(query.as_ref(), cli.flag("--prune"))?);
+        return Ok(());
+    }
+    let Some(query) = query else {
+        if cli.flag("--paths") || cli.flag("--sample") {
+            bail!("Listing or sampling paths needs --from and --to");
+        }
+        return aoc_common::run(11, day11::solve);
     };
 
-    let graph = Graph::from_file(&dir.join("day11.txt"))?;
-    let part1 = graph.count_paths("you", "out");
-    let part2 = graph.part2("svr", "out", false, false);
-
-    println!("Part 1: {part1}");
-    println!("Part 2: {part2}");
-
-    Ok(())
-}
-
-#[derive(Debug)]
-struct Graph {
-    nodes: HashMap<String, HashSet<String>>,
-}
-
-impl Graph {
-    fn from_file(p: impl AsRef<Path>) -> anyhow::Result<Self> {
-        let f = File::open(p)?;
-        let reader = std::io::BufReader::new(f);
-
-        let mut nodes = HashMap::new();
-
-        for line in reader.lines() {
-            let Ok(line) = line else {
-                continue;
-            };
-
-            let parts = line
-                .split([' ', ':'])
-                .filter(|s| !s.is_empty())
-                .collect::<Vec<_>>();
-
-            let vertex = parts[0].to_string();
-            let mut outputs = HashSet::new();
-            for edge in &parts[1..] {
-                outputs.insert(edge.to_string());
-            }
-
-            nodes.insert(vertex, outputs);
+    let graph = cli.read_input(11)?.parse::<Graph>()?;
+    if cli.flag("--paths") {
+        for path in graph.paths(&query)? {
+            println!("{}", path.join(" -> "));
         }
-
-        Ok(Self { nodes })
-    }
-
-    fn count_paths(&self, start: &str, end: &str) -> usize {
-        if start == end {
-            return 1;
+    } else if let Some(samples) = cli.value("--sample")? {
+        let samples = samples
+            .parse::<usize>()
+            .with_context(|| format!("Invalid number of samples {samples}"))?;
+        let seed = match cli.value("--seed")? {
+            Some(seed) => seed
+                .parse::<u64>()
+                .with_context(|| format!("Invalid seed {seed}"))?,
+            None => 0,
+        };
+
+        let sampler = graph.sampler(&query)?;
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..samples {
+            let Some(path) = sampler.sample(&mut rng) else {
+                bail!("There are no paths to sample");
+            };
+            println!("{}", path.join(" -> "));
         }
-
-        self.nodes
-            .get(start)
-            .expect("Node not found!")
-            .par_iter()
-            .map(|node| self.count_paths(node, end))
-            .sum()
-    }
-
-    fn part2(&self, start: &str, end: &str, visited_dac: bool, visited_fft: bool) -> usize {
-        part2_helper(&self.nodes, start, end, visited_dac, visited_fft)
-    }
-}
-
-#[cached(
-    ty = "SizedCache<String, usize>",
-    create = "{ SizedCache::with_size(1000) }",
-    convert = r#"{ format!("{}:{},{},{}", start, end, dac, fft) }"#
-)]
-fn part2_helper(
-    nodes: &HashMap<String, HashSet<String>>,
-    start: &str,
-    end: &str,
-    dac: bool,
-    fft: bool,
-) -> usize {
-    if start == end {
-        return if dac && fft { 1 } else { 0 };
-    }
-
-    nodes
-        .get(start)
-        .expect("Node not found!")
-        .par_iter()
-        .map(|node| part2_helper(nodes, node, end, dac || node == "dac", fft || node == "fft"))
-        .sum()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part1() {
-        let graph = Graph::from_file("../test_input/day11.txt").unwrap();
-        assert_eq!(graph.count_paths("you", "out"), 5);
+    } else {
+        println!("{}", graph.count(&query)?);
     }
 
-    #[test]
-    fn test_part2() {
-        let graph = Graph::from_file("../test_input/day11part2.txt").unwrap();
-        assert_eq!(graph.part2("svr", "out", false, false), 2);
-    }
+    Ok(())
 }
@@ -0,0 +1,216 @@
+// Listing and sampling the paths of a query, both steered by its path table: a step is only taken
+// if some path from there on still meets the waypoint constraints, so the listing never goes down
+// a dead end and the sampler can weigh every step by how many paths lie behind it.
+
+use crate::{Graph, PathQuery, PathTable};
+use num_bigint::BigUint;
+use rand::Rng;
+
+/// Every path of a query, found one at a time by a depth first walk
+pub struct Paths<'a> {
+    graph: &'a Graph,
+    table: PathTable<BigUint>,
+    // The path so far: each node, the index of its next edge to try and the waypoints visited
+    // up to and including it
+    stack: Vec<(usize, usize, usize)>,
+}
+
+impl<'a> Iterator for Paths<'a> {
+    type Item = Vec<&'a str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&(node, next, seen)) = self.stack.last() {
+            if node == self.table.end {
+                let path = self.path();
+                self.stack.pop();
+                return Some(path);
+            }
+
+            let edges = &self.graph.edges[node][next..];
+            match edges.iter().position(|&to| self.table.reachable(to, seen)) {
+                Some(i) => {
+                    let to = edges[i];
+                    self.stack.last_mut().unwrap().1 = next + i + 1;
+                    self.stack.push((to, 0, seen | self.table.bits[to]));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> Paths<'a> {
+    fn path(&self) -> Vec<&'a str> {
+        self.stack
+            .iter()
+            .map(|&(node, _, _)| self.graph.names[node].as_str())
+            .collect()
+    }
+}
+
+/// Draws paths of a query uniformly at random
+pub struct PathSampler<'a> {
+    graph: &'a Graph,
+    table: PathTable<BigUint>,
+    total: BigUint,
+}
+
+impl<'a> PathSampler<'a> {
+    /// How many paths there are to pick from
+    pub fn total(&self) -> &BigUint {
+        &self.total
+    }
+
+    /// A random path, each one equally likely, or None if there are none. Picks a number below
+    /// the total and walks to the path with that number, skipping over the paths behind every
+    /// edge not taken.
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<Vec<&'a str>> {
+        if self.total == BigUint::ZERO {
+            return None;
+        }
+
+        let mut pick = random_below(&self.total, rng);
+        let mut node = self.table.start;
+        let mut seen = self.table.bits[node];
+        let mut path = vec![self.graph.names[node].as_str()];
+        while node != self.table.end {
+            for &to in &self.graph.edges[node] {
+                let behind = self.table.completions_from(to, seen);
+                if pick < behind {
+                    node = to;
+                    break;
+                }
+                pick -= behind;
+            }
+            seen |= self.table.bits[node];
+            path.push(self.graph.names[node].as_str());
+        }
+
+        Some(path)
+    }
+}
+
+impl PathTable<BigUint> {
+    fn completions_from(&self, node: usize, seen: usize) -> BigUint {
+        self.completions(node, seen, BigUint::ZERO)
+            .expect("big integers don't overflow")
+    }
+
+    fn reachable(&self, node: usize, seen: usize) -> bool {
+        self.completions_from(node, seen) != BigUint::ZERO
+    }
+}
+
+impl Graph {
+    /// Lazily lists every path of the query, as node names
+    pub fn paths(&self, query: &PathQuery) -> anyhow::Result<Paths<'_>> {
+        let table = self.big_path_table(query)?;
+        let stack = match table.reachable(table.start, 0) {
+            true => vec![(table.start, 0, table.bits[table.start])],
+            false => Vec::new(),
+        };
+
+        Ok(Paths {
+            graph: self,
+            table,
+            stack,
+        })
+    }
+
+    pub fn sampler(&self, query: &PathQuery) -> anyhow::Result<PathSampler<'_>> {
+        let table = self.big_path_table(query)?;
+        let total = table.completions_from(table.start, 0);
+
+        Ok(PathSampler {
+            graph: self,
+            table,
+            total,
+        })
+    }
+
+    fn big_path_table(&self, query: &PathQuery) -> anyhow::Result<PathTable<BigUint>> {
+        let table = self.path_table(query, BigUint::ZERO, BigUint::from(1u8))?;
+        Ok(table.expect("big integers don't overflow"))
+    }
+}
+
+// Uniform in 0..bound: random bits as wide as the bound, tried again until they're below it
+fn random_below(bound: &BigUint, rng: &mut impl Rng) -> BigUint {
+    let bits = bound.bits();
+    loop {
+        let digits = (0..bits.div_ceil(32))
+            .map(|_| rng.random::<u32>())
+            .collect();
+        let n = BigUint::new(digits) >> (bits.div_ceil(32) * 32 - bits);
+        if n < *bound {
+            return n;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::collections::HashMap;
+
+    fn graph() -> Graph {
+        let input = aoc_common::read_test_input("day11part2.txt").unwrap();
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn test_paths() {
+        let graph = graph();
+        let query = PathQuery {
+            via: vec!["dac".to_string(), "fft".to_string()],
+            ..PathQuery::new("svr", "out")
+        };
+        let paths = graph.paths(&query).unwrap().collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                [
+                    "svr", "aaa", "fft", "ccc", "eee", "dac", "fff", "ggg", "out"
+                ],
+                [
+                    "svr", "aaa", "fft", "ccc", "eee", "dac", "fff", "hhh", "out"
+                ],
+            ]
+        );
+
+        assert_eq!(
+            graph.paths(&PathQuery::new("svr", "out")).unwrap().count(),
+            8
+        );
+        let query = PathQuery {
+            avoid: vec!["out".to_string()],
+            ..PathQuery::new("svr", "out")
+        };
+        assert_eq!(graph.paths(&query).unwrap().next(), None);
+    }
+
+    #[test]
+    fn test_sampler() {
+        let graph = graph();
+        let sampler = graph.sampler(&PathQuery::new("svr", "out")).unwrap();
+        assert_eq!(*sampler.total(), BigUint::from(8u8));
+
+        // Every one of the 8 paths comes up about 4000 / 8 times
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut seen = HashMap::new();
+        for _ in 0..4000 {
+            *seen.entry(sampler.sample(&mut rng).unwrap()).or_insert(0) += 1;
+        }
+        assert_eq!(seen.len(), 8);
+        assert!(seen.values().all(|&n| (400..600).contains(&n)));
+
+        let first = sampler.sample(&mut StdRng::seed_from_u64(1));
+        assert_eq!(first, sampler.sample(&mut StdRng::seed_from_u64(1)));
+    }
+}
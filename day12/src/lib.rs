@@ -0,0 +1,255 @@
+mod packing;
+mod render;
+mod shape;
+
+pub use packing::{Method, Verdict};
+pub use render::Layout;
+pub use shape::Shape;
+
+use anyhow::{Context, bail};
+use aoc_common::{Answers, Timer};
+use packing::Region;
+use std::time::{Duration, Instant};
+
+// How long the search may spend on one region before giving up on it
+const SEARCH_LIMIT: Duration = Duration::from_secs(10);
+
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    solve_with(input, false, SEARCH_LIMIT)
+}
+
+/// `solve` with a table of how each region was decided as its report
+pub fn solve_verbose(input: &str) -> anyhow::Result<Answers> {
+    solve_with(input, true, SEARCH_LIMIT)
+}
+
+fn solve_with(input: &str, verbose: bool, limit: Duration) -> anyhow::Result<Answers> {
+    let mut timer = Timer::start();
+    let (shapes, regions) = parse(input)?;
+    timer.lap("parse");
+
+    let decisions = regions
+        .iter()
+        .map(|region| {
+            let start = Instant::now();
+            let (verdict, method) = region.decide(&shapes, limit);
+            (verdict, method, start.elapsed())
+        })
+        .collect::<Vec<_>>();
+    let result = decisions
+        .iter()
+        .filter(|(verdict, _, _)| matches!(verdict, Verdict::Fits(_)))
+        .count();
+    timer.lap("part1");
+
+    // A region that timed out might fit or not, so any count would be a guess. With `--verbose`
+    // the table showing which one goes to stderr ahead of the error, there being no answers to
+    // attach it to.
+    let report = verbose.then(|| diagnostics(&shapes, &regions, &decisions));
+    if let Some(i) = decisions
+        .iter()
+        .position(|(verdict, _, _)| *verdict == Verdict::TimedOut)
+    {
+        if let Some(table) = &report {
+            eprint!("{table}");
+        }
+        bail!(
+            "Region {} timed out after {limit:?}, so there's no telling how many regions fit",
+            i + 1
+        );
+    }
+
+    let answers = Answers::part1_only(result).with_timings(timer);
+    Ok(match report {
+        Some(table) => answers.with_report(table),
+        None => answers,
+    })
+}
+
+// A table with every region's size, the area its presents take up, the area left free and the
+// verdict along with what settled it and how long that took, followed by a summary of how the
+// regions were decided
+fn diagnostics(
+    shapes: &[Shape],
+    regions: &[Region],
+    decisions: &[(Verdict, Method, Duration)],
+) -> String {
+    let mut table = format!(
+        "{:>6}  {:>7}  {:>8}  {:>6}  {:>6}  {:>6}  {:<12}  {}\n",
+        "region", "size", "presents", "area", "free", "slack", "verdict", "decided by"
+    );
+    let (mut by_area, mut by_boxes, mut by_search) = (0, 0, 0);
+    let (mut within_area, mut fits, mut timed_out) = (0, 0, 0);
+    for (i, (region, (verdict, method, elapsed))) in regions.iter().zip(decisions).enumerate() {
+        let size = region.width * region.height;
+        let area = region.present_area(shapes);
+        let free = size as i64 - area as i64;
+        within_area += usize::from(free >= 0);
+
+        let verdict = match verdict {
+            Verdict::Fits(_) => {
+                fits += 1;
+                "fits"
+            }
+            Verdict::DoesNotFit => "does not fit",
+            Verdict::TimedOut => {
+                timed_out += 1;
+                "timed out"
+            }
+        };
+        let method = match method {
+            Method::AreaBound => {
+                by_area += 1;
+                "area bound".to_string()
+            }
+            Method::BoxBound => {
+                by_boxes += 1;
+                "box bound".to_string()
+            }
+            Method::Search => {
+                by_search += 1;
+                format!("search ({elapsed:.2?})")
+            }
+        };
+        table += &format!(
+            "{:>6}  {:>7}  {:>8}  {area:>6}  {free:>6}  {:>5.1}%  {verdict:<12}  {method}\n",
+            i + 1,
+            format!("{}x{}", region.width, region.height),
+            region.counts.iter().sum::<usize>(),
+            free as f64 * 100.0 / size.max(1) as f64,
+        );
+    }
+
+    table += &format!(
+        "\nDecided by the area bound: {by_area}, by the box bound: {by_boxes}, by search: \
+         {by_search} ({timed_out} timed out)\nThe area bound alone would accept {within_area} of \
+         {} regions, {fits} actually fit\n",
+        regions.len()
+    );
+
+    table
+}
+
+/// Whether each region fits its presents, and how if it does, in input order
+pub fn verdicts(input: &str) -> anyhow::Result<Vec<Verdict>> {
+    let (shapes, regions) = parse(input)?;
+    Ok(regions
+        .iter()
+        .map(|region| region.pack(&shapes, SEARCH_LIMIT))
+        .collect())
+}
+
+// The `N:` shape blocks, numbered 0, 1, 2, ... in order, followed by the `WxH: counts` regions
+fn parse(input: &str) -> anyhow::Result<(Vec<Shape>, Vec<Region>)> {
+    let mut shapes = Vec::new();
+    let mut regions = Vec::new();
+
+    let mut lines = input.lines().enumerate().peekable();
+    while let Some((i, line)) = lines.next() {
+        let line = line.trim();
+        let Some((head, rest)) = line.split_once(':') else {
+            if line.is_empty() {
+                continue;
+            }
+            bail!("Line {}: expected a shape or a region, got {line}", i + 1);
+        };
+
+        if let Some((width, height)) = head.split_once('x') {
+            let region = Region::from_parts(width, height, rest, shapes.len())
+                .with_context(|| format!("Line {}: invalid region {line}", i + 1))?;
+            regions.push(region);
+            continue;
+        }
+
+        let index = head
+            .parse::<usize>()
+            .with_context(|| format!("Line {}: invalid shape index {head}", i + 1))?;
+        if index != shapes.len() {
+            bail!(
+                "Line {}: expected shape {}, got {index}",
+                i + 1,
+                shapes.len()
+            );
+        }
+        if !regions.is_empty() {
+            bail!("Line {}: shape {index} comes after the regions", i + 1);
+        }
+        if !rest.trim().is_empty() {
+            bail!(
+                "Line {}: expected the grid of shape {index} on the next lines",
+                i + 1
+            );
+        }
+
+        let mut grid = String::new();
+        while let Some((_, row)) = lines.next_if(|(_, l)| !l.trim().is_empty()) {
+            grid += row.trim();
+            grid.push('\n');
+        }
+        let shape = grid
+            .parse::<Shape>()
+            .with_context(|| format!("Line {}: invalid shape {index}", i + 1))?;
+        shapes.push(shape);
+    }
+
+    Ok((shapes, regions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = aoc_common::read_test_input("day12.txt").unwrap();
+        let (shapes, regions) = parse(&input).unwrap();
+        assert_eq!(shapes.len(), 6);
+        assert_eq!(shapes[5].to_string(), "###\n.#.\n###\n");
+        assert_eq!(regions.len(), 3);
+        assert_eq!((regions[1].width, regions[1].height), (12, 5));
+        assert_eq!(regions[1].counts, [1, 0, 1, 0, 2, 2]);
+
+        assert!(parse("1:\n#\n").is_err());
+        assert!(parse("0:\n#\n\n0:\n#\n").is_err());
+        assert!(parse("0:\n##\n#\n").is_err());
+        assert!(parse("0:\n#\n\n2x2: 1 1\n").is_err());
+        assert!(parse("0:\n#\n\n2x: 1\n").is_err());
+        assert!(parse("0:\n#\n\n2x2: 1\n1:\n#\n").is_err());
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        // Three L-trominoes have the area for a 3x3 region but can't tile it
+        let answers = solve_verbose("0:\n##\n#.\n\n1x1: 1\n4x2: 2\n3x2: 2\n3x3: 3\n").unwrap();
+        assert_eq!(answers.part1.as_deref(), Some("2"));
+        let table = answers.report.unwrap();
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 8);
+        assert!(lines[1].ends_with("3      -2  -200.0%  does not fit  area bound"));
+        assert!(lines[2].ends_with("6       2   25.0%  fits          box bound"));
+        assert!(lines[3].contains("0.0%  fits          search ("));
+        assert!(lines[4].contains("0.0%  does not fit  search ("));
+        assert_eq!(
+            lines[6],
+            "Decided by the area bound: 1, by the box bound: 1, by search: 2 (0 timed out)"
+        );
+        assert_eq!(
+            lines[7],
+            "The area bound alone would accept 3 of 4 regions, 2 actually fit"
+        );
+    }
+
+    #[test]
+    fn test_timed_out() {
+        let input = "0:\n##\n#.\n\n4x2: 2\n3x3: 3\n";
+        for verbose in [false, true] {
+            let error = solve_with(input, verbose, Duration::ZERO).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "Region 2 timed out after 0ns, so there's no telling how many regions fit"
+            );
+            assert_eq!(error.chain().count(), 1);
+        }
+        assert!(solve_with(input, false, SEARCH_LIMIT).is_ok());
+    }
+}
@@ -1,72 +1,72 @@
-use std::fs::File;
-use std::io::{BufRead, Read};
-use std::path::PathBuf;
+use anyhow::{Context, bail};
+use aoc_common::Cli;
+use day12::Verdict;
+use std::path::Path;
 
-fn main() -> anyhow::Result<()> {
-    let args = std::env::args().collect::<Vec<_>>();
-    let dir = if args.contains(&String::from("--test_input")) {
-        PathBuf::from("test_input")
-    } else {
-        PathBuf::from("input")
-    };
-
-    let f = File::open(dir.join("day12.txt"))?;
-    let mut reader = std::io::BufReader::new(f);
-
-    let mut buffer = String::new();
-    let mut sizes: Vec<usize> = Vec::new();
-    let mut index = 0;
-    let mut trees = Vec::new();
-
-    while reader.read_line(&mut buffer)? > 0 {
-        let line = buffer.trim();
-        if line.is_empty() {
-            index += 1;
-            buffer.clear();
-            continue;
-        }
-        if line.contains('x') {
-            trees.push(line.to_string());
-            buffer.clear();
-            break;
-        }
-        if line.contains(':') {
-            sizes.push(0);
-            buffer.clear();
-            continue;
-        }
-        sizes[index] += line.chars().into_iter().filter(|c| *c == '#').count();
+// Pixels per cell in images
+const SCALE: usize = 20;
 
-        buffer.clear();
+fn main() -> anyhow::Result<()> {
+    // `--render` draws how the presents fit in ASCII and `--image FILE` writes it as an .svg or
+    // .ppm, both for every region or just the one picked with `--region N`
+    let cli = Cli::from_env()?;
+    let image = cli.value("--image")?;
+    if !cli.flag("--render") && image.is_none() {
+        // `--verbose` adds a table of every region after the answer, on stderr in JSON mode
+        let solve = match cli.flag("--verbose") {
+            true => day12::solve_verbose,
+            false => day12::solve,
+        };
+        return aoc_common::run(12, solve);
     }
 
-    trees.extend(reader.lines().into_iter().map(|l| l.unwrap()));
-
-    let mut result = 0;
-    for tree in trees {
-        let tokens = tree.trim().split(':').collect::<Vec<_>>();
-        let size = tokens[0]
-            .split('x')
-            .map(|s| s.parse::<usize>().unwrap())
-            .product::<usize>();
-        let counts = tokens[1]
-            .split_whitespace()
-            .map(|s| s.parse::<usize>().unwrap())
-            .collect::<Vec<_>>();
+    let picked = match cli.value("--region")? {
+        Some(n) => Some(
+            n.parse::<usize>()
+                .with_context(|| format!("Invalid region number {n}"))?,
+        ),
+        None => None,
+    };
+    let verdicts = day12::verdicts(&cli.read_input(12)?)?;
+    let regions = verdicts
+        .iter()
+        .enumerate()
+        .map(|(i, verdict)| (i + 1, verdict))
+        .filter(|(n, _)| picked.is_none_or(|p| p == *n))
+        .collect::<Vec<_>>();
+    if regions.is_empty() {
+        bail!("There is no region {}", picked.unwrap_or(0));
+    }
 
-        let required_size = counts
-            .iter()
-            .zip(sizes.iter())
-            .map(|(c, s)| c * s)
-            .sum::<usize>();
-        if required_size <= size {
-            result += 1;
+    if cli.flag("--render") {
+        for (n, verdict) in &regions {
+            match verdict {
+                Verdict::Fits(layout) => println!("Region {n}: fits\n{layout}"),
+                Verdict::DoesNotFit => println!("Region {n}: does not fit\n"),
+                Verdict::TimedOut => println!("Region {n}: timed out\n"),
+            }
         }
-
-        buffer.clear();
     }
 
-    println!("Part 1: {result}");
+    if let Some(path) = image {
+        let [(n, verdict)] = regions[..] else {
+            bail!("--image writes a single region, pick one with --region N");
+        };
+        let Verdict::Fits(layout) = verdict else {
+            bail!("Region {n} has no layout to draw: {verdict:?}");
+        };
+        let path = Path::new(path);
+        let contents = match path.extension().and_then(|e| e.to_str()) {
+            Some("svg") => layout.to_svg(SCALE).into_bytes(),
+            Some("ppm") => layout.to_ppm(SCALE),
+            _ => bail!(
+                "Unknown image format for {}, expected .svg or .ppm",
+                path.display()
+            ),
+        };
+        std::fs::write(path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,345 @@
+// Decides whether a region fits its presents. Cheap bounds settle most regions: too little area
+// means they can't fit, and enough room to give every present its own bounding box means they
+// do. Anything else is searched: the first empty cell of the region is either covered by some
+// orientation of a remaining present, anchored on that orientation's first cell, or left empty.
+// Every cell left empty uses up some of the region's spare area, which bounds the search.
+
+use crate::render::Layout;
+use crate::shape::Shape;
+use anyhow::{Context, bail};
+use std::time::{Duration, Instant};
+
+// How often the search looks at the clock, starting with its first step
+const CLOCK_STEPS: usize = 1 << 12;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    // With one way the presents can be laid out
+    Fits(Layout),
+    DoesNotFit,
+    // The search ran out of time before it could tell
+    TimedOut,
+}
+
+/// How a region's verdict was reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    // The presents cover more cells than the region has
+    AreaBound,
+    // Every present gets a box of its own
+    BoxBound,
+    Search,
+}
+
+/// A region under a tree and how many of each present have to go in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Region {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) counts: Vec<usize>,
+}
+
+impl Region {
+    // From the `W`, `H` and `counts` of a `WxH: counts` line, with one count per shape
+    pub(crate) fn from_parts(
+        width: &str,
+        height: &str,
+        counts: &str,
+        shapes: usize,
+    ) -> anyhow::Result<Self> {
+        let number = |s: &str| {
+            s.trim()
+                .parse::<usize>()
+                .with_context(|| format!("{s} is not a number"))
+        };
+        let counts = counts
+            .split_whitespace()
+            .map(number)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if counts.len() != shapes {
+            bail!("{} counts for {shapes} shapes", counts.len());
+        }
+
+        Ok(Self {
+            width: number(width)?,
+            height: number(height)?,
+            counts,
+        })
+    }
+
+    pub(crate) fn pack(&self, shapes: &[Shape], limit: Duration) -> Verdict {
+        self.decide(shapes, limit).0
+    }
+
+    // Cells the presents cover between them
+    pub(crate) fn present_area(&self, shapes: &[Shape]) -> usize {
+        self.counts
+            .iter()
+            .zip(shapes)
+            .map(|(n, s)| n * s.area())
+            .sum()
+    }
+
+    // The verdict along with what settled it
+    pub(crate) fn decide(&self, shapes: &[Shape], limit: Duration) -> (Verdict, Method) {
+        let area = self.present_area(shapes);
+        if area > self.width * self.height {
+            return (Verdict::DoesNotFit, Method::AreaBound);
+        }
+
+        // Every present fits in a box this big, one way round or the other
+        let (rows, cols) = shapes
+            .iter()
+            .map(|s| s.bounding_box())
+            .fold((0, 0), |(r, c), (sr, sc)| (r.max(sr), c.max(sc)));
+        let boxes =
+            |rows: usize, cols: usize| (self.height / rows.max(1)) * (self.width / cols.max(1));
+        let presents = self.counts.iter().sum::<usize>();
+        if boxes(rows, cols) >= presents {
+            return (
+                Verdict::Fits(self.boxed(shapes, rows, cols)),
+                Method::BoxBound,
+            );
+        }
+        if boxes(cols, rows) >= presents {
+            return (
+                Verdict::Fits(self.boxed(shapes, cols, rows)),
+                Method::BoxBound,
+            );
+        }
+
+        // Every orientation is tried anyway, so the region can be turned to make its rows the
+        // short side, which keeps the boundary between filled and empty cells short
+        let (width, height) = (self.width.min(self.height), self.width.max(self.height));
+        let mut search = Search {
+            width,
+            height,
+            filled: vec![false; width * height],
+            orientations: shapes.iter().map(Placement::all).collect(),
+            counts: self.counts.clone(),
+            slack: self.width * self.height - area,
+            steps: 0,
+            deadline: Instant::now() + limit,
+            timed_out: false,
+            placed: Vec::new(),
+        };
+        let verdict = match (search.run(0), search.timed_out) {
+            (true, _) => Verdict::Fits(self.layout(&search)),
+            (false, true) => Verdict::TimedOut,
+            (false, false) => Verdict::DoesNotFit,
+        };
+
+        (verdict, Method::Search)
+    }
+
+    // Every present in a box of its own, the boxes in rows from the top left
+    fn boxed(&self, shapes: &[Shape], rows: usize, cols: usize) -> Layout {
+        let mut layout = Layout::new(self.width, self.height);
+        let per_row = self.width / cols;
+        let presents = self
+            .counts
+            .iter()
+            .enumerate()
+            .flat_map(|(shape, &n)| std::iter::repeat_n(shape, n));
+        for (i, shape) in presents.enumerate() {
+            let orientation = shapes[shape]
+                .orientations()
+                .into_iter()
+                .find(|o| {
+                    let (r, c) = o.bounding_box();
+                    r <= rows && c <= cols
+                })
+                .expect("the boxes are big enough for every present one way round");
+            let (top, left) = (i / per_row * rows, i % per_row * cols);
+            let cells = orientation
+                .cells()
+                .iter()
+                .map(|&(r, c)| (top + r, left + c))
+                .collect();
+            layout.place(shape, cells);
+        }
+
+        layout
+    }
+
+    // The search's placements, turned back the right way if the search had the region turned
+    fn layout(&self, search: &Search) -> Layout {
+        let mut layout = Layout::new(self.width, self.height);
+        let turned = search.width != self.width;
+        for (shape, cells) in &search.placed {
+            let cells = cells
+                .iter()
+                .map(|&i| {
+                    let (r, c) = (i / search.width, i % search.width);
+                    if turned { (c, r) } else { (r, c) }
+                })
+                .collect();
+            layout.place(*shape, cells);
+        }
+
+        layout
+    }
+}
+
+// One orientation of a present as offsets from its first cell
+struct Placement {
+    offsets: Vec<(isize, isize)>,
+}
+
+impl Placement {
+    fn all(shape: &Shape) -> Vec<Placement> {
+        shape
+            .orientations()
+            .iter()
+            .map(|o| {
+                let (r0, c0) = o.cells()[0];
+                let offsets = o
+                    .cells()
+                    .iter()
+                    .map(|&(r, c)| (r as isize - r0 as isize, c as isize - c0 as isize))
+                    .collect();
+                Placement { offsets }
+            })
+            .collect()
+    }
+}
+
+struct Search {
+    width: usize,
+    height: usize,
+    filled: Vec<bool>,
+    // Every orientation of every present
+    orientations: Vec<Vec<Placement>>,
+    // Presents still to place
+    counts: Vec<usize>,
+    // Cells that can still be left empty
+    slack: usize,
+    steps: usize,
+    deadline: Instant,
+    timed_out: bool,
+    // Each present placed so far with the cells it covers
+    placed: Vec<(usize, Vec<usize>)>,
+}
+
+impl Search {
+    fn run(&mut self, from: usize) -> bool {
+        if self.counts.iter().all(|&n| n == 0) {
+            return true;
+        }
+
+        self.steps += 1;
+        if self.steps % CLOCK_STEPS == 1 && Instant::now() >= self.deadline {
+            self.timed_out = true;
+        }
+        if self.timed_out {
+            return false;
+        }
+
+        let Some(cell) = (from..self.filled.len()).find(|&i| !self.filled[i]) else {
+            return false;
+        };
+
+        for shape in 0..self.orientations.len() {
+            if self.counts[shape] == 0 {
+                continue;
+            }
+            for o in 0..self.orientations[shape].len() {
+                let Some(cells) = self.cells(cell, &self.orientations[shape][o]) else {
+                    continue;
+                };
+
+                cells.iter().for_each(|&i| self.filled[i] = true);
+                self.counts[shape] -= 1;
+                self.placed.push((shape, cells));
+                // Once found, the placements are kept for the layout
+                if self.run(cell + 1) {
+                    return true;
+                }
+                let (_, cells) = self.placed.pop().unwrap();
+                self.counts[shape] += 1;
+                cells.iter().for_each(|&i| self.filled[i] = false);
+            }
+        }
+
+        if self.slack > 0 {
+            self.filled[cell] = true;
+            self.slack -= 1;
+            let found = self.run(cell + 1);
+            self.slack += 1;
+            self.filled[cell] = false;
+            return found;
+        }
+
+        false
+    }
+
+    // The cells `placement` covers when anchored on `cell`, if they're all free
+    fn cells(&self, cell: usize, placement: &Placement) -> Option<Vec<usize>> {
+        let (row, col) = ((cell / self.width) as isize, (cell % self.width) as isize);
+        placement
+            .offsets
+            .iter()
+            .map(|&(dr, dc)| {
+                let (r, c) = (row + dr, col + dc);
+                let inside =
+                    (0..self.height as isize).contains(&r) && (0..self.width as isize).contains(&c);
+                let i = (r * self.width as isize + c) as usize;
+                (inside && !self.filled[i]).then_some(i)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shapes() -> Vec<Shape> {
+        let input = aoc_common::read_test_input("day12.txt").unwrap();
+        crate::parse(&input).unwrap().0
+    }
+
+    fn region(width: usize, height: usize, counts: &[usize]) -> Region {
+        Region {
+            width,
+            height,
+            counts: counts.to_vec(),
+        }
+    }
+
+    fn assert_fits(region: Region, shapes: &[Shape], limit: Duration) {
+        let Verdict::Fits(layout) = region.pack(shapes, limit) else {
+            panic!("{region:?} should fit");
+        };
+        assert!(layout.is_valid(shapes, &region.counts));
+        assert_eq!((layout.width, layout.height), (region.width, region.height));
+    }
+
+    #[test]
+    fn test_sample_regions() {
+        let shapes = shapes();
+        let limit = Duration::from_secs(60);
+        assert_fits(region(4, 4, &[0, 0, 0, 0, 2, 0]), &shapes, limit);
+        assert_fits(region(12, 5, &[1, 0, 1, 0, 2, 2]), &shapes, limit);
+        assert_eq!(
+            region(12, 5, &[1, 0, 1, 0, 3, 2]).pack(&shapes, limit),
+            Verdict::DoesNotFit
+        );
+    }
+
+    #[test]
+    fn test_bounds() {
+        let shapes = shapes();
+        let limit = Duration::ZERO;
+        assert_eq!(
+            region(3, 3, &[0, 0, 0, 0, 2, 0]).pack(&shapes, limit),
+            Verdict::DoesNotFit
+        );
+        assert_fits(region(9, 6, &[1, 1, 1, 1, 1, 1]), &shapes, limit);
+        assert_fits(region(3, 12, &[0, 4, 0, 0, 0, 0]), &shapes, limit);
+        assert_eq!(
+            region(12, 5, &[1, 0, 1, 0, 3, 2]).pack(&shapes, limit),
+            Verdict::TimedOut
+        );
+    }
+}
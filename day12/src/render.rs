@@ -0,0 +1,272 @@
+use crate::shape::Shape;
+use std::fmt::{Display, Formatter};
+
+// Labels for the presents in ASCII, each told apart from its neighbours' rather than unique
+const LABELS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+// Fill colour of each shape index in images, reused from the start past the 12th
+const PALETTE: [[u8; 3]; 12] = [
+    [230, 25, 75],
+    [60, 180, 75],
+    [255, 225, 25],
+    [0, 130, 200],
+    [245, 130, 48],
+    [145, 30, 180],
+    [70, 240, 240],
+    [240, 50, 230],
+    [210, 245, 60],
+    [250, 190, 212],
+    [0, 128, 128],
+    [170, 110, 40],
+];
+const EMPTY: [u8; 3] = [255, 255, 255];
+
+/// Where every present went in a region: its shape index and the (row, column) cells it covers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub width: usize,
+    pub height: usize,
+    pub presents: Vec<(usize, Vec<(usize, usize)>)>,
+}
+
+impl Layout {
+    pub(crate) fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            presents: Vec::new(),
+        }
+    }
+
+    pub(crate) fn place(&mut self, shape: usize, cells: Vec<(usize, usize)>) {
+        self.presents.push((shape, cells));
+    }
+
+    /// Checks the layout really packs `counts` of each of `shapes`: every present lies inside
+    /// the region, is some orientation of its shape, and overlaps no other
+    pub fn is_valid(&self, shapes: &[Shape], counts: &[usize]) -> bool {
+        let mut placed = vec![0; shapes.len()];
+        for (shape, cells) in &self.presents {
+            let Some(expected) = shapes.get(*shape) else {
+                return false;
+            };
+            if !expected.orientations().iter().any(|o| same_cells(o, cells)) {
+                return false;
+            }
+            placed[*shape] += 1;
+        }
+
+        placed == counts && self.owners().is_some()
+    }
+
+    // Index of the present covering each cell in row-major order, None if two overlap or one
+    // sticks out of the region
+    fn owners(&self) -> Option<Vec<Option<usize>>> {
+        let mut owners = vec![None; self.width * self.height];
+        for (i, (_, cells)) in self.presents.iter().enumerate() {
+            for &(r, c) in cells {
+                if r >= self.height || c >= self.width {
+                    return None;
+                }
+                if owners[r * self.width + c].replace(i).is_some() {
+                    return None;
+                }
+            }
+        }
+
+        Some(owners)
+    }
+
+    fn owners_or_empty(&self) -> Vec<Option<usize>> {
+        self.owners()
+            .unwrap_or_else(|| vec![None; self.width * self.height])
+    }
+
+    // A label for each present, the first one none of the presents already labelled next to it
+    // has. Only one with more neighbours than there are labels can end up sharing a neighbour's.
+    fn labels(&self) -> Vec<char> {
+        let owners = self.owners_or_empty();
+        let mut picked: Vec<usize> = Vec::with_capacity(self.presents.len());
+        for (i, (_, cells)) in self.presents.iter().enumerate() {
+            let mut taken = [false; LABELS.len()];
+            for &(r, c) in cells {
+                let around = [
+                    (r.wrapping_sub(1), c),
+                    (r + 1, c),
+                    (r, c.wrapping_sub(1)),
+                    (r, c + 1),
+                ];
+                for (r, c) in around {
+                    if r >= self.height || c >= self.width {
+                        continue;
+                    }
+                    if let Some(j) = owners[r * self.width + c].filter(|&j| j < i) {
+                        taken[picked[j]] = true;
+                    }
+                }
+            }
+            picked.push(taken.iter().position(|&t| !t).unwrap_or(i % LABELS.len()));
+        }
+
+        picked.into_iter().map(|l| LABELS[l] as char).collect()
+    }
+
+    /// Binary PPM, `scale` pixels per cell, each present filled with the colour of its shape and
+    /// a thin dark line wherever two presents meet
+    pub fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        let owners = self.owners_or_empty();
+        let (w, h) = (self.width * scale, self.height * scale);
+        let mut ppm = format!("P6\n{w} {h}\n255\n").into_bytes();
+        for y in 0..h {
+            for x in 0..w {
+                let (r, c) = (y / scale, x / scale);
+                let owner = owners[r * self.width + c];
+                // The first pixel row and column of a cell show a border to a different owner
+                let border = (y % scale == 0 && r > 0 && owners[(r - 1) * self.width + c] != owner)
+                    || (x % scale == 0 && c > 0 && owners[r * self.width + c - 1] != owner);
+                let colour = match owner {
+                    _ if border => [40, 40, 40],
+                    Some(i) => colour(self.presents[i].0),
+                    None => EMPTY,
+                };
+                ppm.extend_from_slice(&colour);
+            }
+        }
+
+        ppm
+    }
+
+    /// SVG with one square per cell, each present filled with the colour of its shape and
+    /// labelled like in the ASCII rendering
+    pub fn to_svg(&self, scale: usize) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+            self.width * scale,
+            self.height * scale
+        );
+        svg += &format!(
+            "  <rect width=\"100%\" height=\"100%\" fill=\"{}\" stroke=\"black\"/>\n",
+            hex(EMPTY)
+        );
+        for ((shape, cells), label) in self.presents.iter().zip(self.labels()) {
+            svg += &format!(
+                "  <g fill=\"{}\" stroke=\"black\"><title>{label} (shape {shape})</title>\n",
+                hex(colour(*shape)),
+            );
+            for &(r, c) in cells {
+                svg += &format!(
+                    "    <rect x=\"{}\" y=\"{}\" width=\"{scale}\" height=\"{scale}\"/>\n",
+                    c * scale,
+                    r * scale
+                );
+            }
+            svg += "  </g>\n";
+        }
+        svg += "</svg>\n";
+
+        svg
+    }
+}
+
+// The region grid with every present shown by a letter none of its neighbours share and empty
+// cells as `.`
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let owners = self.owners_or_empty();
+        let labels = self.labels();
+        for row in owners.chunks(self.width.max(1)) {
+            let line = row
+                .iter()
+                .map(|owner| owner.map_or('.', |i| labels[i]))
+                .collect::<String>();
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn same_cells(shape: &Shape, cells: &[(usize, usize)]) -> bool {
+    let top = cells.iter().map(|&(r, _)| r).min().unwrap_or(0);
+    let left = cells.iter().map(|&(_, c)| c).min().unwrap_or(0);
+    let mut cells = cells
+        .iter()
+        .map(|&(r, c)| (r - top, c - left))
+        .collect::<Vec<_>>();
+    cells.sort_unstable();
+    cells == shape.cells()
+}
+
+fn colour(shape: usize) -> [u8; 3] {
+    PALETTE[shape % PALETTE.len()]
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> Layout {
+        let mut layout = Layout::new(3, 2);
+        layout.place(0, vec![(0, 0), (0, 1), (1, 0)]);
+        layout.place(1, vec![(1, 1), (1, 2)]);
+        layout
+    }
+
+    #[test]
+    fn test_ascii() {
+        assert_eq!(layout().to_string(), "AA.\nABB\n");
+    }
+
+    #[test]
+    fn test_many_labels() {
+        // 104 single cells in two rows, so the first row's labels would all come round again
+        // right below them if they were handed out in order
+        let mut layout = Layout::new(52, 2);
+        for cell in 0..104 {
+            layout.place(0, vec![(cell / 52, cell % 52)]);
+        }
+        let rows = layout
+            .to_string()
+            .lines()
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for c in 0..52 {
+            assert_ne!(rows[0][c], rows[1][c]);
+            if c + 1 < 52 {
+                assert_ne!(rows[0][c], rows[0][c + 1]);
+                assert_ne!(rows[1][c], rows[1][c + 1]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_valid() {
+        let shapes = ["##\n#.", "##"].map(|s| s.parse::<Shape>().unwrap());
+        assert!(layout().is_valid(&shapes, &[1, 1]));
+        assert!(!layout().is_valid(&shapes, &[1, 2]));
+        assert!(!layout().is_valid(&shapes[..1], &[1]));
+
+        let mut overlapping = layout();
+        overlapping.place(1, vec![(0, 1), (0, 2)]);
+        assert!(!overlapping.is_valid(&shapes, &[1, 2]));
+    }
+
+    #[test]
+    fn test_images() {
+        let ppm = layout().to_ppm(2);
+        assert!(ppm.starts_with(b"P6\n6 4\n255\n"));
+        assert_eq!(ppm.len(), "P6\n6 4\n255\n".len() + 6 * 4 * 3);
+
+        let svg = layout().to_svg(10);
+        assert!(
+            svg.starts_with(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"30\" height=\"20\">"
+            )
+        );
+        assert_eq!(svg.matches("<rect x=").count(), 5);
+        assert!(svg.contains("<title>B (shape 1)</title>"));
+    }
+}
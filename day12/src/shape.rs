@@ -0,0 +1,169 @@
+use anyhow::bail;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The cells of a present as (row, column) pairs, shifted so the top row and the leftmost column
+/// are both 0, in row-major order
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shape {
+    cells: Vec<(usize, usize)>,
+}
+
+// A grid of `#` for the present and `.` for empty space, all rows the same width
+impl FromStr for Shape {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let lines = s.lines().collect::<Vec<_>>();
+        let Some(width) = lines.first().map(|l| l.len()) else {
+            bail!("Shape has no rows");
+        };
+
+        for (row, line) in lines.iter().enumerate() {
+            if line.len() != width {
+                bail!(
+                    "Shape row {} is {} wide, expected {width}",
+                    row + 1,
+                    line.len()
+                );
+            }
+            if let Some(c) = line.chars().find(|c| !matches!(c, '#' | '.')) {
+                bail!("Shape row {} has {c:?}, expected only # and .", row + 1);
+            }
+        }
+
+        let cells = lines.iter().enumerate().flat_map(|(row, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '#')
+                .map(move |(col, _)| (row, col))
+        });
+        let shape = Self::normalized(cells);
+        if shape.area() == 0 {
+            bail!("Shape has no # cells");
+        }
+
+        Ok(shape)
+    }
+}
+
+impl Shape {
+    fn normalized(cells: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let cells = cells.into_iter().collect::<Vec<_>>();
+        let top = cells.iter().map(|&(r, _)| r).min().unwrap_or(0);
+        let left = cells.iter().map(|&(_, c)| c).min().unwrap_or(0);
+        let mut cells = cells
+            .into_iter()
+            .map(|(r, c)| (r - top, c - left))
+            .collect::<Vec<_>>();
+        cells.sort_unstable();
+
+        Self { cells }
+    }
+
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Rows and columns of the smallest box around the present
+    pub fn bounding_box(&self) -> (usize, usize) {
+        let rows = self.cells.iter().map(|&(r, _)| r + 1).max().unwrap_or(0);
+        let cols = self.cells.iter().map(|&(_, c)| c + 1).max().unwrap_or(0);
+        (rows, cols)
+    }
+
+    /// The four rotations, each followed by its mirror image. Symmetric shapes repeat some.
+    pub fn symmetries(&self) -> [Shape; 8] {
+        let mut shape = self.clone();
+        std::array::from_fn(|i| {
+            if i % 2 == 1 {
+                return shape.flipped();
+            }
+            if i > 0 {
+                shape = shape.rotated();
+            }
+            shape.clone()
+        })
+    }
+
+    /// The same representative for every shape that is a rotation or mirror image of this one
+    pub fn canonical(&self) -> Shape {
+        self.symmetries().into_iter().min().unwrap()
+    }
+
+    /// Every distinct way the present can lie
+    pub fn orientations(&self) -> Vec<Shape> {
+        let mut orientations = self.symmetries().to_vec();
+        orientations.sort_unstable();
+        orientations.dedup();
+        orientations
+    }
+
+    // A quarter turn clockwise
+    fn rotated(&self) -> Self {
+        let (rows, _) = self.bounding_box();
+        Self::normalized(self.cells.iter().map(|&(r, c)| (c, rows - 1 - r)))
+    }
+
+    // Mirrored left to right
+    fn flipped(&self) -> Self {
+        let (_, cols) = self.bounding_box();
+        Self::normalized(self.cells.iter().map(|&(r, c)| (r, cols - 1 - c)))
+    }
+}
+
+// The grid it was parsed from, without any empty border
+impl Display for Shape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (rows, cols) = self.bounding_box();
+        for row in 0..rows {
+            let line = (0..cols)
+                .map(|col| match self.cells.binary_search(&(row, col)) {
+                    Ok(_) => '#',
+                    Err(_) => '.',
+                })
+                .collect::<String>();
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        let shape = "..#.\n.###\n....".parse::<Shape>().unwrap();
+        assert_eq!(shape.cells(), [(0, 1), (1, 0), (1, 1), (1, 2)]);
+        assert_eq!(shape.bounding_box(), (2, 3));
+        assert_eq!(shape.to_string(), ".#.\n###\n");
+
+        assert!("##\n#".parse::<Shape>().is_err());
+        assert!("#x\n##".parse::<Shape>().is_err());
+        assert!("..\n..".parse::<Shape>().is_err());
+    }
+
+    #[test]
+    fn test_orientations() {
+        let shape = "###\n##.\n##.".parse::<Shape>().unwrap();
+        assert_eq!(shape.area(), 7);
+        assert_eq!(shape.bounding_box(), (3, 3));
+        assert_eq!(shape.orientations().len(), 8);
+
+        let turned = "###\n###\n..#".parse::<Shape>().unwrap();
+        assert!(shape.orientations().contains(&turned));
+        assert_eq!(shape.canonical(), turned.canonical());
+
+        // Symmetric both ways, so it only ever lies flat or on its side
+        let shape = "###\n.#.\n###".parse::<Shape>().unwrap();
+        assert_eq!(shape.orientations().len(), 2);
+        assert_eq!(shape.symmetries().len(), 8);
+    }
+}